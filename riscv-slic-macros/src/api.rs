@@ -1,8 +1,34 @@
+use crate::input::{CodegenInput, OutOfRangeInput};
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub fn api_mod() -> TokenStream {
+/// Helper function for handling out-of-range interrupt numbers according to the policy.
+fn out_of_range(policy: &OutOfRangeInput) -> TokenStream {
+    let body = match policy {
+        OutOfRangeInput::Ignore => quote!(Ok(T::default())),
+        OutOfRangeInput::Error => quote!(Err(riscv_slic::Error::InvalidInterrupt(interrupt))),
+        OutOfRangeInput::Fault(hook) => quote! {
+            let err = riscv_slic::Error::InvalidInterrupt(interrupt);
+            #hook(err);
+            Err(err)
+        },
+    };
+    quote! {
+        /// Handles a request with an out-of-range interrupt number.
+        #[inline]
+        #[allow(unreachable_code, unused_variables)]
+        fn __riscv_slic_out_of_range<T: Default>(interrupt: u16) -> Result<T, riscv_slic::Error> {
+            #body
+        }
+    }
+}
+
+pub fn api_mod(input: &CodegenInput) -> TokenStream {
+    let out_of_range = out_of_range(&input.out_of_range);
+
     quote!(
+        #out_of_range
+
         /// Enables the software interrupt controller and triggers a software interrupt if ready.
         ///
         /// # Safety
@@ -80,7 +106,7 @@ pub fn api_mod() -> TokenStream {
         /// You must return the previous threshold to the SLIC after you are done.
         #[inline]
         #[no_mangle]
-        pub unsafe fn __riscv_slic_raise_threshold(priority: u8) -> Result<u8, ()> {
+        pub unsafe fn __riscv_slic_raise_threshold(priority: u8) -> Result<u8, riscv_slic::Error> {
             critical_section::with(|cs| {
                 let (res, is_ready) = {
                     let mut slic = __SLIC.borrow_ref_mut(cs);
@@ -95,6 +121,33 @@ pub fn api_mod() -> TokenStream {
             })
         }
 
+        /// Checks that a given interrupt number corresponds to a software interrupt source.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_check_interrupt(
+            interrupt: u16,
+        ) -> Result<(), riscv_slic::Error> {
+            critical_section::with(|cs| __SLIC.borrow_ref(cs).check_interrupt(interrupt))
+        }
+
+        /// Checks that a given priority level is valid for the application.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_check_priority(priority: u8) -> Result<(), riscv_slic::Error> {
+            match priority > __MAX_PRIORITY {
+                true => Err(riscv_slic::Error::PriorityOutOfRange),
+                false => Ok(()),
+            }
+        }
+
         /// Returns the interrupt priority of a given software interrupt source.
         ///
         /// # Safety
@@ -102,21 +155,95 @@ pub fn api_mod() -> TokenStream {
         /// This function is only for `riscv-slic` internal use. Do not call it directly.
         #[inline]
         #[no_mangle]
-        pub unsafe fn __riscv_slic_get_priority(interrupt: u16) -> u8 {
-            critical_section::with(|cs| __SLIC.borrow_ref(cs).get_priority(interrupt))
+        pub unsafe fn __riscv_slic_get_priority(interrupt: u16) -> Result<u8, riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            Ok(critical_section::with(|cs| __SLIC.borrow_ref(cs).get_priority(interrupt)))
         }
 
         /// Sets the interrupt priority of a given software interrupt source in the SLIC.
+        /// If the software interrupt is already pending, it is re-queued with its new priority.
+        /// Users of shared resources cannot take priorities above the ceilings of their resources.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_set_priority(interrupt: u16, priority: u8) -> Result<(), riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            let ceiling = __PRIORITY_LIMITS[interrupt as usize];
+            if priority > ceiling {
+                return Err(riscv_slic::Error::PriorityAboveCeiling { ceiling });
+            }
+            critical_section::with(|cs| {
+                if {
+                    let mut slic = __SLIC.borrow_ref_mut(cs);
+                    slic.set_priority(interrupt, priority);
+                    slic.is_ready()
+                } {
+                    // trigger a software interrupt if the SLIC is still ready at this point
+                    __riscv_slic_swi_pend();
+                }
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if a given software interrupt source is enabled.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_is_interrupt_enabled(interrupt: u16) -> Result<bool, riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            Ok(critical_section::with(|cs| __SLIC.borrow_ref(cs).is_interrupt_enabled(interrupt)))
+        }
+
+        /// Enables a software interrupt source and triggers a software interrupt if ready.
         ///
         /// # Safety
         ///
         /// This function is only for `riscv-slic` internal use. Do not call it directly.
         #[inline]
         #[no_mangle]
-        pub unsafe fn __riscv_slic_set_priority(interrupt: u16, priority: u8) {
+        pub unsafe fn __riscv_slic_enable_interrupt(interrupt: u16) -> Result<(), riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
             critical_section::with(|cs| {
-                __SLIC.borrow_ref_mut(cs).set_priority(interrupt, priority)
+                if {
+                    let mut slic = __SLIC.borrow_ref_mut(cs);
+                    slic.enable_interrupt(interrupt)
+                } {
+                    // trigger a software interrupt if the SLIC is still ready at this point
+                    __riscv_slic_swi_pend();
+                }
             });
+            Ok(())
+        }
+
+        /// Disables a software interrupt source.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_disable_interrupt(interrupt: u16) -> Result<(), riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            critical_section::with(|cs| {
+                __SLIC.borrow_ref_mut(cs).disable_interrupt(interrupt);
+            });
+            Ok(())
         }
 
         /// Marks a software interrupt as pending.
@@ -126,7 +253,10 @@ pub fn api_mod() -> TokenStream {
         /// This function is only for `riscv-slic` internal use. Do not call it directly.
         #[inline]
         #[no_mangle]
-        pub unsafe fn __riscv_slic_pend(interrupt: u16) {
+        pub unsafe fn __riscv_slic_pend(interrupt: u16) -> Result<(), riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
             critical_section::with(|cs| {
                 if {
                     let mut slic = __SLIC.borrow_ref_mut(cs);
@@ -136,6 +266,50 @@ pub fn api_mod() -> TokenStream {
                     __riscv_slic_swi_pend();
                 }
             });
+            Ok(())
+        }
+
+        /// Returns `true` if a given software interrupt source is pending.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_is_pending(interrupt: u16) -> Result<bool, riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            Ok(critical_section::with(|cs| __SLIC.borrow_ref(cs).is_pending(interrupt)))
+        }
+
+        /// Returns `true` if the handler of a given software interrupt source is running.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_is_running(interrupt: u16) -> Result<bool, riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            Ok(critical_section::with(|cs| __SLIC.borrow_ref(cs).is_running(interrupt)))
+        }
+
+        /// Clears the pending state of a software interrupt.
+        /// Returns `true` if the software interrupt was pending.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_unpend(interrupt: u16) -> Result<bool, riscv_slic::Error> {
+            if interrupt as usize >= __SOFTWARE_INTERRUPTS.len() {
+                return __riscv_slic_out_of_range(interrupt);
+            }
+            Ok(critical_section::with(|cs| __SLIC.borrow_ref_mut(cs).unpend(interrupt)))
         }
 
         /// Polls the SLIC for pending software interrupts and runs them.
@@ -161,6 +335,7 @@ pub fn api_mod() -> TokenStream {
                 }
             }) {
                 __SOFTWARE_INTERRUPTS[int as usize]();
+                critical_section::with(|cs| __SLIC.borrow_ref_mut(cs).complete(int));
                 // SAFETY: we restore the previous threshold after the function is done
                 unsafe { __riscv_slic_set_threshold(prev) };
             }
@@ -12,3 +12,33 @@ pub use mecall::{export_quote, ExportBackendInput};
 mod ssoft;
 #[cfg(feature = "ssoft-backend")]
 pub use ssoft::{export_quote, ExportBackendInput};
+
+#[cfg(feature = "sim-backend")]
+mod sim;
+#[cfg(feature = "sim-backend")]
+pub use sim::{export_quote, ExportBackendInput};
+
+#[cfg(feature = "aclint-sswi-backend")]
+mod aclint_sswi;
+#[cfg(feature = "aclint-sswi-backend")]
+pub use aclint_sswi::{export_quote, ExportBackendInput};
+
+#[cfg(any(feature = "sbi-backend", feature = "sim-sbi-backend"))]
+mod sbi;
+#[cfg(any(feature = "sbi-backend", feature = "sim-sbi-backend"))]
+pub use sbi::{export_quote, ExportBackendInput};
+
+#[cfg(feature = "clic-backend")]
+mod clic;
+#[cfg(feature = "clic-backend")]
+pub use clic::{export_quote, max_priority, new_slic, ExportBackendInput};
+
+#[cfg(feature = "imsic-backend")]
+mod imsic;
+#[cfg(feature = "imsic-backend")]
+pub use imsic::{export_quote, swi_handler_attribute, ExportBackendInput};
+
+#[cfg(feature = "plic-backend")]
+mod plic;
+#[cfg(feature = "plic-backend")]
+pub use plic::{export_quote, ExportBackendInput};
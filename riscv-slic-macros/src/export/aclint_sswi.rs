@@ -0,0 +1,88 @@
+use crate::input::CodegenInput;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Expr, Ident, Result, Token,
+};
+
+pub struct ExportBackendInput {
+    /// The base address of the ACLINT SSWI device
+    base: Expr,
+    /// The identifier of the HART whose `SETSSIP` register is used
+    hart_id: Ident,
+}
+
+impl Parse for ExportBackendInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut base = None;
+        let mut hart_id = None;
+
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "base" => {
+                    if base.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    base = Some(content.parse()?);
+                }
+                "hart_id" => {
+                    if hart_id.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    hart_id = Some(content.parse()?);
+                }
+                _ => return Err(Error::new(ident.span(), "invalid identifier")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?; // consume the ',' between identifiers
+            }
+        }
+
+        Ok(Self {
+            base: base.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            hart_id: hart_id.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+        })
+    }
+}
+
+pub fn export_quote(input: &CodegenInput) -> TokenStream {
+    let pac = &input.pac;
+    let backend = input.backend.as_ref().unwrap();
+    let base = &backend.base;
+    let hart_id = &backend.hart_id;
+    quote! {
+        /// Triggers a supervisor software interrupt via the `SETSSIP` register of the ACLINT SSWI device.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_pend() {
+            use riscv_slic::riscv::HartIdNumber;
+            let hart_id = #pac::interrupt::Hart::#hart_id.number();
+            let setssip = (#base as usize as *mut u32).add(hart_id);
+            setssip.write_volatile(1);
+        }
+
+        /// Clears the Supervisor Software Interrupt Pending bit in the `SIP` register.
+        ///
+        /// Writing 0 to `SETSSIP` has no effect, as the ACLINT SSWI only signals
+        /// edges to the hart. Thus, the pending bit must be cleared in the `SIP` register.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_unpend() {
+            riscv_slic::riscv::register::sip::clear_ssoft();
+        }
+    }
+}
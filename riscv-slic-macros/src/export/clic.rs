@@ -0,0 +1,249 @@
+use crate::input::CodegenInput;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Error, Expr, Ident, LitInt, Result, Token,
+};
+
+/// CLIC local interrupt line assigned to a software interrupt, declared as `<name> = <line>`.
+struct LineInput {
+    /// The name of the software interrupt source
+    swi: Ident,
+    /// The variant of the `CoreInterrupt` enum of the PAC for the CLIC line
+    line: Ident,
+}
+
+impl Parse for LineInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let swi = input.parse()?;
+        input.parse::<Token![=]>()?; // consume the '='
+        let line = input.parse()?;
+        Ok(Self { swi, line })
+    }
+}
+
+pub struct ExportBackendInput {
+    /// The base address of the CLIC peripheral
+    base: Expr,
+    /// The number of interrupt level bits of the CLIC (i.e., `cliccfg.nlbits`)
+    level_bits: u8,
+    /// The CLIC local interrupt lines assigned to the software interrupts
+    lines: Vec<LineInput>,
+}
+
+impl Parse for ExportBackendInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut base = None;
+        let mut level_bits = None;
+        let mut lines = None;
+
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "base" => {
+                    if base.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    base = Some(content.parse()?);
+                }
+                "level_bits" => {
+                    if level_bits.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    let lit: LitInt = content.parse()?;
+                    let bits: u8 = lit.base10_parse()?;
+                    if !(1..=8).contains(&bits) {
+                        return Err(Error::new(lit.span(), "level bits must be between 1 and 8"));
+                    }
+                    level_bits = Some(bits);
+                }
+                "lines" => {
+                    if lines.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    let lines_content;
+                    syn::bracketed!(lines_content in content);
+                    let parsed = lines_content.parse_terminated(LineInput::parse, Token![,])?;
+                    lines = Some(parsed.into_iter().collect());
+                }
+                _ => return Err(Error::new(ident.span(), "invalid identifier")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?; // consume the ',' between identifiers
+            }
+        }
+
+        Ok(Self {
+            base: base.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            level_bits: level_bits.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            lines: lines.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+        })
+    }
+}
+
+/// Returns the CLIC lines in the order of the software interrupts, checking that the input is supported.
+fn check_input(input: &CodegenInput) -> Result<Vec<&Ident>> {
+    let backend = input.backend.as_ref().unwrap();
+    let max_level = (1u16 << backend.level_bits) - 1;
+    if input.priorities.is_some_and(|max| max as u16 > max_level) {
+        return Err(Error::new(
+            backend.base.span(),
+            "the CLIC does not have enough level bits for all the priorities",
+        ));
+    }
+    for (i, line) in backend.lines.iter().enumerate() {
+        if !input.swi_handlers.iter().any(|swi| swi.ident == line.swi) {
+            return Err(Error::new(line.swi.span(), "unknown software interrupt"));
+        }
+        if backend.lines[..i].iter().any(|l| l.swi == line.swi) {
+            return Err(Error::new(line.swi.span(), "duplicate software interrupt"));
+        }
+        if backend.lines[..i].iter().any(|l| l.line == line.line) {
+            return Err(Error::new(line.line.span(), "duplicate CLIC line"));
+        }
+    }
+    input
+        .swi_handlers
+        .iter()
+        .map(|swi| {
+            if swi.priority as u16 > max_level {
+                return Err(Error::new(swi.ident.span(), "priority out of range"));
+            }
+            match backend.lines.iter().find(|line| line.swi == swi.ident) {
+                Some(line) => Ok(&line.line),
+                None => Err(Error::new(swi.ident.span(), "missing CLIC line")),
+            }
+        })
+        .collect()
+}
+
+/// Returns the highest valid priority level of the application, which must fit in the level bits.
+pub fn max_priority(input: &CodegenInput) -> u8 {
+    let max_level = u8::MAX >> (8 - input.backend.as_ref().unwrap().level_bits);
+    input.priorities.unwrap_or(max_level)
+}
+
+/// Returns the constructor of the static SLIC instance on top of the CLIC.
+pub fn new_slic(input: &CodegenInput) -> TokenStream {
+    let level_bits = input.backend.as_ref().unwrap().level_bits;
+    let priorities = input.swi_handlers.iter().map(|swi| swi.priority);
+    let counting = input.swi_handlers.iter().map(|swi| match &input.counting {
+        Some(counting) => counting.contains(&swi.ident),
+        None => false,
+    });
+    quote!(riscv_slic::new_slic(#level_bits, [#(#priorities),*], [#(#counting),*]))
+}
+
+pub fn export_quote(input: &CodegenInput) -> TokenStream {
+    let lines = match check_input(input) {
+        Ok(lines) => lines,
+        Err(err) => return err.to_compile_error(),
+    };
+    let pac = &input.pac;
+    let backend = input.backend.as_ref().unwrap();
+    let base = &backend.base;
+    let indices = 0..input.swi_handlers.len() as u16;
+    let handlers = input
+        .swi_handlers
+        .iter()
+        .map(|swi| format_ident!("__riscv_slic_clic_{}", swi.ident));
+    let start_traps: Vec<_> = lines
+        .iter()
+        .map(|line| format_ident!("_start_{}_trap", line))
+        .collect();
+
+    quote! {
+        /// Offset of the `clicint*` registers from the base address of the CLIC.
+        const __CLICINT_OFFSET: usize = 0x1000;
+
+        extern "C" {
+            fn _start_DefaultHandler_trap();
+            #(fn #start_traps();)*
+        }
+
+        /// Number of entries of the vector table of the CLIC.
+        const __CLIC_VECTORS_LEN: usize = {
+            let lines = [#(#pac::interrupt::CoreInterrupt::#lines as usize),*];
+            let (mut len, mut i) = (0, 0);
+            while i < lines.len() {
+                if lines[i] >= len {
+                    len = lines[i] + 1;
+                }
+                i += 1;
+            }
+            len
+        };
+
+        /// Vector table of the CLIC. Only the lines of software interrupts are hardware vectored.
+        #[repr(C, align(64))]
+        struct __ClicVectors([unsafe extern "C" fn(); __CLIC_VECTORS_LEN]);
+
+        /// The vector table of the CLIC, which points to the trap handlers generated by `riscv-rt`.
+        static __CLIC_VECTORS: __ClicVectors = {
+            let mut vectors = [_start_DefaultHandler_trap as unsafe extern "C" fn(); __CLIC_VECTORS_LEN];
+            #(vectors[#pac::interrupt::CoreInterrupt::#lines as usize] = #start_traps;)*
+            __ClicVectors(vectors)
+        };
+
+        /// Returns a pointer to the `clicintip` register of the CLIC line of a given software interrupt.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_clic_int(interrupt: u16) -> *mut u8 {
+            let lines = [#(riscv_slic::riscv::InterruptNumber::number(#pac::interrupt::CoreInterrupt::#lines)),*];
+            (#base as usize + __CLICINT_OFFSET + 4 * lines[interrupt as usize]) as *mut u8
+        }
+
+        /// Returns the address of the vector table of the CLIC.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_clic_vectors() -> usize {
+            &__CLIC_VECTORS as *const __ClicVectors as usize
+        }
+
+        /// Does nothing, as the CLIC triggers the lines of pending software interrupts in hardware.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_pend() {}
+
+        /// Runs the handler of a software interrupt from the trap handler of its CLIC line.
+        #[inline]
+        unsafe fn __riscv_slic_clic_dispatch(interrupt: u16) {
+            critical_section::with(|cs| __SLIC.borrow_ref_mut(cs).start(interrupt));
+            // nested traps overwrite the previous interrupt level stored in mcause
+            let mcause: usize;
+            core::arch::asm!("csrr {}, mcause", out(reg) mcause);
+            // We nest the handler to let interrupts with higher levels trigger
+            riscv_slic::nested(|| __SOFTWARE_INTERRUPTS[interrupt as usize]());
+            core::arch::asm!("csrw mcause, {}", in(reg) mcause);
+            critical_section::with(|cs| __SLIC.borrow_ref_mut(cs).complete(interrupt));
+        }
+
+        #(
+            /// Trap handler of the CLIC line of a software interrupt.
+            #[riscv_rt::core_interrupt(#pac::interrupt::CoreInterrupt::#lines)]
+            #[allow(non_snake_case)]
+            unsafe fn #handlers() {
+                __riscv_slic_clic_dispatch(#indices);
+            }
+        )*
+    }
+}
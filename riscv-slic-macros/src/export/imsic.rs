@@ -0,0 +1,207 @@
+use crate::input::CodegenInput;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Expr, Ident, LitInt, Result, Token,
+};
+
+/// IMSIC interrupt file used for triggering software interrupts.
+#[derive(Clone, Copy)]
+enum File {
+    Machine,
+    Supervisor,
+}
+
+impl File {
+    /// Returns the numbers of the `*iselect` and `*ireg` CSRs of the interrupt file.
+    fn csrs(self) -> (u16, u16) {
+        match self {
+            Self::Machine => (0x350, 0x351),
+            Self::Supervisor => (0x150, 0x151),
+        }
+    }
+
+    /// Returns the number of the `*topei` CSR of the interrupt file.
+    fn topei(self) -> u16 {
+        match self {
+            Self::Machine => 0x35C,
+            Self::Supervisor => 0x15C,
+        }
+    }
+}
+
+pub struct ExportBackendInput {
+    /// The interrupt file of the HART (machine or supervisor)
+    file: File,
+    /// The base address of the interrupt file of the HART
+    address: Expr,
+    /// The interrupt identity used for triggering software interrupts
+    identity: u16,
+}
+
+impl Parse for ExportBackendInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut file = None;
+        let mut address = None;
+        let mut identity = None;
+
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "file" => {
+                    if file.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    let value: Ident = content.parse()?;
+                    file = Some(match value.to_string().as_str() {
+                        "machine" => File::Machine,
+                        "supervisor" => File::Supervisor,
+                        _ => return Err(Error::new(value.span(), "invalid interrupt file")),
+                    });
+                }
+                "address" => {
+                    if address.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    address = Some(content.parse()?);
+                }
+                "identity" => {
+                    if identity.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    let lit: LitInt = content.parse()?;
+                    let value: u16 = lit.base10_parse()?;
+                    if !(1..=2047).contains(&value) {
+                        return Err(Error::new(
+                            lit.span(),
+                            "interrupt identity must be between 1 and 2047",
+                        ));
+                    }
+                    identity = Some(value);
+                }
+                _ => return Err(Error::new(ident.span(), "invalid identifier")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?; // consume the ',' between identifiers
+            }
+        }
+
+        Ok(Self {
+            file: file.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            address: address.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            identity: identity.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+        })
+    }
+}
+
+/// Returns the attribute of the software interrupt handler, which runs on external interrupts.
+pub fn swi_handler_attribute(input: &CodegenInput) -> TokenStream {
+    let pac = &input.pac;
+    match input.backend.as_ref().unwrap().file {
+        File::Machine => quote! {
+            #[riscv_rt::core_interrupt(#pac::interrupt::CoreInterrupt::MachineExternal)]
+        },
+        File::Supervisor => quote! {
+            #[riscv_rt::core_interrupt(#pac::interrupt::CoreInterrupt::SupervisorExternal)]
+        },
+    }
+}
+
+pub fn export_quote(input: &CodegenInput) -> TokenStream {
+    let backend = input.backend.as_ref().unwrap();
+    let address = &backend.address;
+    let identity = backend.identity;
+    let (iselect, ireg) = backend.file.csrs();
+    let write_iselect = format!("csrw {iselect:#x}, {{}}");
+    let set_ireg = format!("csrs {ireg:#x}, {{}}");
+    let claim_topei = format!("csrrw {{}}, {:#x}, zero", backend.file.topei());
+    let write_ireg = format!("csrw {ireg:#x}, {{}}");
+    let (set_ext, clear_ext) = match backend.file {
+        File::Machine => (
+            quote!(riscv_slic::riscv::register::mie::set_mext()),
+            quote!(riscv_slic::riscv::register::mie::clear_mext()),
+        ),
+        File::Supervisor => (
+            quote!(riscv_slic::riscv::register::sie::set_sext()),
+            quote!(riscv_slic::riscv::register::sie::clear_sext()),
+        ),
+    };
+
+    quote! {
+        /// Triggers a software interrupt by writing its identity to the `seteipnum` register of the IMSIC.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_pend() {
+            // seteipnum_le is the first register of the interrupt file
+            let seteipnum = (#address as usize) as *mut u32;
+            seteipnum.write_volatile(#identity as u32);
+        }
+
+        /// Claims the pending interrupt identities of the interrupt file via `*topei` until none is left.
+        /// Identities other than the one of software interrupts are dispatched
+        /// with the `_dispatch_external_interrupt` function of the PAC.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_unpend() {
+            extern "C" {
+                fn _dispatch_external_interrupt(code: usize);
+            }
+            loop {
+                // writing *topei claims the identity that it reports
+                let topei: usize;
+                core::arch::asm!(#claim_topei, out(reg) topei);
+                match riscv_slic::imsic::topei_identity(topei) {
+                    0 => break,
+                    #identity => {}
+                    identity => _dispatch_external_interrupt(identity as usize),
+                }
+            }
+        }
+
+        /// Enables the interrupt identity of software interrupts and the external interrupts of the IMSIC.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_enable() {
+            // eidelivery: enable interrupt delivery from the interrupt file
+            core::arch::asm!(#write_iselect, in(reg) riscv_slic::imsic::EIDELIVERY);
+            core::arch::asm!(#write_ireg, in(reg) 1usize);
+            // eithreshold: do not mask any interrupt identity
+            core::arch::asm!(#write_iselect, in(reg) riscv_slic::imsic::EITHRESHOLD);
+            core::arch::asm!(#write_ireg, in(reg) 0usize);
+            // eie*: enable the interrupt identity
+            let (eie, mask) = riscv_slic::imsic::eie(#identity);
+            core::arch::asm!(#write_iselect, in(reg) eie);
+            core::arch::asm!(#set_ireg, in(reg) mask);
+            #set_ext;
+        }
+
+        /// Disables the external interrupts of the IMSIC.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_disable() {
+            #clear_ext;
+        }
+    }
+}
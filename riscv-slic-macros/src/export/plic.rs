@@ -0,0 +1,139 @@
+use crate::input::CodegenInput;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Ident, Path, Result, Token,
+};
+
+pub struct ExportBackendInput {
+    /// The path to the PLIC peripheral of the PAC
+    plic: Path,
+    /// The identifier of the HART of the PLIC context
+    context: Ident,
+    /// The identifier of the external interrupt source used for triggering software interrupts
+    source: Ident,
+}
+
+impl Parse for ExportBackendInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut plic = None;
+        let mut context = None;
+        let mut source = None;
+
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "plic" => {
+                    if plic.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    plic = Some(content.parse()?);
+                }
+                "context" => {
+                    if context.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    context = Some(content.parse()?);
+                }
+                "source" => {
+                    if source.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    source = Some(content.parse()?);
+                }
+                _ => return Err(Error::new(ident.span(), "invalid identifier")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?; // consume the ',' between identifiers
+            }
+        }
+
+        Ok(Self {
+            plic: plic.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            context: context.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            source: source.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+        })
+    }
+}
+
+pub fn export_quote(input: &CodegenInput) -> TokenStream {
+    let pac = &input.pac;
+    let backend = input.backend.as_ref().unwrap();
+    let plic = &backend.plic;
+    let context = &backend.context;
+    let source = &backend.source;
+    quote! {
+        /// Triggers a machine external interrupt by setting the pending bit of the PLIC source.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        /// The PLIC source must have a software-settable pending bit.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_pend() {
+            use riscv_slic::riscv::InterruptNumber;
+            use riscv_slic::riscv_peripheral::plic::Plic;
+            let source = #pac::interrupt::ExternalInterrupt::#source.number();
+            riscv_slic::plic::set_pending(<#plic as Plic>::BASE, source);
+        }
+
+        /// Claims the pending interrupt of the PLIC context and completes it.
+        /// If the claimed source is not the source of software interrupts,
+        /// it is dispatched with the `_dispatch_external_interrupt` function of the PAC before completing it.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_unpend() {
+            use riscv_slic::riscv::InterruptNumber;
+            extern "C" {
+                fn _dispatch_external_interrupt(code: usize);
+            }
+            let claim = #plic::ctx(#pac::interrupt::Hart::#context).claim();
+            if let Some(source) = claim.claim::<#pac::interrupt::ExternalInterrupt>() {
+                let code = source.number();
+                if code != #pac::interrupt::ExternalInterrupt::#source.number() {
+                    _dispatch_external_interrupt(code);
+                }
+                claim.complete(source);
+            }
+        }
+
+        /// Enables the PLIC source with the highest priority and machine external interrupts.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_enable() {
+            use riscv_slic::riscv::PriorityNumber;
+            let source = #pac::interrupt::ExternalInterrupt::#source;
+            let max_priority = #pac::interrupt::Priority::MAX_PRIORITY_NUMBER;
+            let priority = #pac::interrupt::Priority::from_number(max_priority).unwrap();
+            #plic::priorities().set_priority(source, priority);
+            #plic::ctx(#pac::interrupt::Hart::#context).enables().enable(source);
+            riscv_slic::riscv::register::mie::set_mext();
+        }
+
+        /// Disables machine external interrupts.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_disable() {
+            riscv_slic::riscv::register::mie::clear_mext();
+        }
+    }
+}
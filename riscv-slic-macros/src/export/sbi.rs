@@ -0,0 +1,164 @@
+use crate::input::CodegenInput;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Ident, Path, Result, Token,
+};
+
+/// HART that receives the IPIs.
+enum HartInput {
+    /// A given variant of the `Hart` enum of the PAC
+    Fixed(Ident),
+    /// The current HART, as returned by a user hook
+    Current(Path),
+}
+
+impl Parse for HartInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        if ident == "current" && input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            return Ok(Self::Current(content.parse()?));
+        }
+        Ok(Self::Fixed(ident))
+    }
+}
+
+/// Implementation of the environment calls to the SBI.
+enum EcallInput {
+    /// The `ecall` instruction
+    Instruction,
+    /// A user hook (e.g., a stand-in for the SBI implementation)
+    Hook(Path),
+}
+
+impl Parse for EcallInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "instruction" => Ok(Self::Instruction),
+            "hook" => {
+                let content;
+                syn::parenthesized!(content in input);
+                Ok(Self::Hook(content.parse()?))
+            }
+            _ => Err(Error::new(
+                ident.span(),
+                "invalid ecall (expected `instruction` or `hook(<hook>)`)",
+            )),
+        }
+    }
+}
+
+pub struct ExportBackendInput {
+    /// The HART that receives the IPIs
+    hart_id: HartInput,
+    /// The implementation of the environment calls
+    ecall: EcallInput,
+}
+
+impl Parse for ExportBackendInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut hart_id = None;
+        let mut ecall = None;
+
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "hart_id" => {
+                    if hart_id.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    hart_id = Some(content.parse()?);
+                }
+                "ecall" => {
+                    if ecall.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    content.parse::<Token![=]>()?; // consume the '='
+                    ecall = Some(content.parse()?);
+                }
+                _ => return Err(Error::new(ident.span(), "invalid identifier")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?; // consume the ',' between identifiers
+            }
+        }
+
+        Ok(Self {
+            hart_id: hart_id.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            ecall: ecall.unwrap_or(EcallInput::Instruction),
+        })
+    }
+}
+
+pub fn export_quote(input: &CodegenInput) -> TokenStream {
+    let pac = &input.pac;
+    let backend = input.backend.as_ref().unwrap();
+    let hart_id = match &backend.hart_id {
+        HartInput::Fixed(hart_id) => quote! {
+            use riscv_slic::riscv::HartIdNumber;
+            let hart_id = #pac::interrupt::Hart::#hart_id.number();
+        },
+        HartInput::Current(hook) => quote! {
+            let hart_id: usize = #hook();
+        },
+    };
+    let ecall = match &backend.ecall {
+        EcallInput::Instruction => quote! {
+            let error: isize;
+            core::arch::asm!(
+                "ecall",
+                inlateout("a0") arg0 => error,
+                inlateout("a1") arg1 => _,
+                in("a6") fid,
+                in("a7") eid,
+            );
+            error
+        },
+        EcallInput::Hook(hook) => quote! {
+            #hook(eid, fid, arg0, arg1)
+        },
+    };
+    quote! {
+        /// Performs an environment call to the SBI implementation and returns its error code.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_sbi_ecall(eid: usize, fid: usize, arg0: usize, arg1: usize) -> isize {
+            #ecall
+        }
+
+        /// Triggers a supervisor software interrupt via the `sbi_send_ipi` function of the SBI IPI extension.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_pend() {
+            #hart_id
+            // errors are ignored, as there is no way to report them to the caller
+            let _ = riscv_slic::sbi::send_ipi(hart_id);
+        }
+
+        /// Clears the Supervisor Software Interrupt Pending bit in the `SIP` register.
+        ///
+        /// # Safety
+        ///
+        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+        #[inline]
+        #[no_mangle]
+        pub unsafe fn __riscv_slic_swi_unpend() {
+            riscv_slic::sbi::clear_ipi();
+        }
+    }
+}
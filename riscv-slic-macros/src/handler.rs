@@ -0,0 +1,81 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident, ItemFn, Result, ReturnType};
+
+/// Checks that the signature of a software interrupt handler is `fn()`, `fn(<payload>)`,
+/// `fn(<context>)`, or `fn(<context>, <payload>)`.
+fn check_signature(f: &ItemFn) -> Result<()> {
+    let sig = &f.sig;
+    if let Some(constness) = &sig.constness {
+        return Err(Error::new(constness.span, "handlers cannot be `const`"));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new(asyncness.span, "handlers cannot be `async`"));
+    }
+    if let Some(unsafety) = &sig.unsafety {
+        return Err(Error::new(unsafety.span, "handlers cannot be `unsafe`"));
+    }
+    if let Some(abi) = &sig.abi {
+        return Err(Error::new(
+            abi.extern_token.span,
+            "handlers cannot specify an ABI",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "handlers cannot be generic",
+        ));
+    }
+    if sig.inputs.len() > 2 || sig.variadic.is_some() {
+        return Err(Error::new_spanned(
+            &sig.inputs,
+            "handlers can only have two arguments (the context and the payload)",
+        ));
+    }
+    if let Some(syn::FnArg::Receiver(receiver)) = sig.inputs.first() {
+        return Err(Error::new_spanned(receiver, "handlers cannot be methods"));
+    }
+    match &sig.output {
+        ReturnType::Default => Ok(()),
+        ReturnType::Type(_, ty) => match &**ty {
+            syn::Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(()),
+            _ => Err(Error::new_spanned(ty, "handlers cannot return a value")),
+        },
+    }
+}
+
+/// Generates a software interrupt handler with the ABI and symbol expected by the SLIC.
+pub fn handler(interrupt: Ident, f: ItemFn) -> TokenStream {
+    if let Err(e) = check_signature(&f) {
+        return e.to_compile_error();
+    }
+
+    let symbol = interrupt.to_string();
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = f;
+    let ident = &sig.ident;
+    let inputs = &sig.inputs;
+    // handlers with context or payload are called by a trampoline generated by `codegen!`
+    let function = match inputs.is_empty() {
+        true => quote!(#vis extern "C" fn #ident() #block),
+        false => quote!(#vis fn #ident(#inputs) #block),
+    };
+
+    quote! {
+        // check that the interrupt is a variant of the enum generated by `codegen!`
+        const _: () = {
+            let _ = crate::slic::SoftwareInterrupt::#interrupt;
+        };
+        // check that the handler matches the context and the payload of the interrupt (if any)
+        const _: crate::slic::__handlers::#interrupt = #ident;
+
+        #(#attrs)*
+        #[export_name = #symbol]
+        #function
+    }
+}
@@ -1,5 +1,8 @@
 use syn::parse::Parse;
-use syn::{bracketed, parse::ParseStream, token::Comma, Error, Ident, Path, Result, Token};
+use syn::{
+    bracketed, parse::ParseStream, token::Comma, Error, Expr, Ident, LitInt, Path, Result, Token,
+    Type,
+};
 
 pub use crate::export::ExportBackendInput; // backend-specific input
 
@@ -22,10 +25,173 @@ impl Parse for HandlersInput {
     }
 }
 
+/// Software interrupt source declared as `<name> [: <payload type>; <queue depth>] [= <priority>]`.
+pub struct SwiInput {
+    /// The name of the software interrupt source
+    pub ident: Ident,
+    /// The payload type and queue depth of the software interrupt source (if any)
+    pub payload: Option<(Type, usize)>,
+    /// The static priority of the software interrupt source (0 if not provided)
+    pub priority: u8,
+}
+
+impl Parse for SwiInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        let mut payload = None;
+        if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?; // consume the ':'
+            let ty = input.parse()?;
+            input.parse::<Token![;]>()?; // consume the ';'
+            let lit: LitInt = input.parse()?;
+            let depth: usize = lit.base10_parse()?;
+            if depth == 0 {
+                return Err(Error::new(lit.span(), "queue depth must be greater than 0"));
+            }
+            payload = Some((ty, depth));
+        }
+        let mut priority = 0;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?; // consume the '='
+            priority = input.parse::<LitInt>()?.base10_parse()?;
+        }
+        Ok(Self {
+            ident,
+            payload,
+            priority,
+        })
+    }
+}
+
+pub struct SwisInput(Vec<SwiInput>);
+
+impl core::ops::Deref for SwisInput {
+    type Target = Vec<SwiInput>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Parse for SwisInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let swis = content.parse_terminated(SwiInput::parse, Comma)?;
+        Ok(Self(swis.into_iter().collect()))
+    }
+}
+
+/// Shared resource declared as `<name>: <type> = <initial value> => [<list of users>]`.
+pub struct ResourceInput {
+    /// The name of the shared resource
+    pub ident: Ident,
+    /// The type of the shared resource
+    pub ty: Type,
+    /// The initial value of the shared resource (must be a constant expression)
+    pub init: Expr,
+    /// The software interrupts that use the shared resource
+    pub users: HandlersInput,
+}
+
+impl Parse for ResourceInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?; // consume the ':'
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?; // consume the '='
+        let init = input.parse()?;
+        input.parse::<Token![=>]>()?; // consume the '=>'
+        let users = input.parse()?;
+        Ok(Self {
+            ident,
+            ty,
+            init,
+            users,
+        })
+    }
+}
+
+pub struct ResourcesInput(Vec<ResourceInput>);
+
+impl core::ops::Deref for ResourcesInput {
+    type Target = Vec<ResourceInput>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Parse for ResourcesInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let resources = content.parse_terminated(ResourceInput::parse, Comma)?;
+        Ok(Self(resources.into_iter().collect()))
+    }
+}
+
+/// Policy for requests with out-of-range interrupt numbers.
+pub enum OutOfRangeInput {
+    /// Requests are ignored, and the default value is returned
+    Ignore,
+    /// Requests return an error
+    Error,
+    /// Requests call a user fault hook and return an error
+    Fault(Path),
+}
+
+impl Parse for OutOfRangeInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "ignore" => Ok(Self::Ignore),
+            "error" => Ok(Self::Error),
+            "fault" => {
+                let content;
+                syn::parenthesized!(content in input);
+                Ok(Self::Fault(content.parse()?))
+            }
+            _ => Err(Error::new(
+                ident.span(),
+                "invalid policy (expected `ignore`, `error`, or `fault(<hook>)`)",
+            )),
+        }
+    }
+}
+
+/// Tie-break policy for pending software interrupts with the same priority.
+pub enum TieBreakInput {
+    /// Interrupts are served in the order they were pended
+    Fifo,
+    /// Interrupts are served by ascending interrupt number
+    LowestNumberFirst,
+}
+
+impl Parse for TieBreakInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "lowest_number_first" => Ok(Self::LowestNumberFirst),
+            _ => Err(Error::new(
+                ident.span(),
+                "invalid policy (expected `fifo` or `lowest_number_first`)",
+            )),
+        }
+    }
+}
+
 pub struct CodegenInput {
     pub slic: Path,
     pub pac: Path,
-    pub swi_handlers: HandlersInput,
+    pub swi_handlers: SwisInput,
+    pub counting: Option<HandlersInput>,
+    pub priorities: Option<u8>,
+    pub resources: Option<ResourcesInput>,
+    pub out_of_range: OutOfRangeInput,
+    #[cfg_attr(feature = "clic-backend", allow(dead_code))]
+    pub tie_break: TieBreakInput,
     #[allow(dead_code)]
     pub backend: Option<ExportBackendInput>,
 }
@@ -35,6 +201,11 @@ impl Parse for CodegenInput {
         let mut slic = None;
         let mut pac = None;
         let mut swi_handlers = None;
+        let mut counting: Option<HandlersInput> = None;
+        let mut priorities = None;
+        let mut resources: Option<ResourcesInput> = None;
+        let mut out_of_range = None;
+        let mut tie_break = None;
         let mut backend = None;
 
         while !input.is_empty() {
@@ -61,6 +232,57 @@ impl Parse for CodegenInput {
                     input.parse::<Token![=]>()?; // consume the '='
                     swi_handlers = Some(input.parse()?);
                 }
+                "counting" => {
+                    if counting.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    input.parse::<Token![=]>()?; // consume the '='
+                    counting = Some(input.parse()?);
+                }
+                "priorities" => {
+                    if priorities.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    input.parse::<Token![=]>()?; // consume the '='
+                    let lit: LitInt = input.parse()?;
+                    let n: u8 = lit.base10_parse()?;
+                    if n == 0 {
+                        return Err(Error::new(
+                            lit.span(),
+                            "at least one priority level is required",
+                        ));
+                    }
+                    priorities = Some(n);
+                }
+                "resources" => {
+                    if resources.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    input.parse::<Token![=]>()?; // consume the '='
+                    resources = Some(input.parse()?);
+                }
+                "out_of_range" => {
+                    if out_of_range.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    input.parse::<Token![=]>()?; // consume the '='
+                    out_of_range = Some(input.parse()?);
+                }
+                "tie_break" => {
+                    if tie_break.is_some() {
+                        return Err(Error::new(ident.span(), "duplicate identifier"));
+                    }
+                    input.parse::<Token![=]>()?; // consume the '='
+                    let policy = input.parse()?;
+                    // the CLIC arbitrates software interrupts with the same level in hardware
+                    if cfg!(feature = "clic-backend") {
+                        return Err(Error::new(
+                            ident.span(),
+                            "tie-break policies are not supported by the CLIC backend",
+                        ));
+                    }
+                    tie_break = Some(policy);
+                }
                 "backend" => {
                     if backend.is_some() {
                         return Err(Error::new(ident.span(), "duplicate identifier"));
@@ -80,11 +302,58 @@ impl Parse for CodegenInput {
             None => syn::parse_str("riscv_slic").unwrap(),
         };
 
+        let swi_handlers: SwisInput =
+            swi_handlers.ok_or_else(|| Error::new(input.span(), "missing identifier"))?;
+
+        // counting interrupts must be software interrupts
+        if let Some(counting) = &counting {
+            for ident in counting.iter() {
+                match swi_handlers.iter().find(|swi| swi.ident == *ident) {
+                    None => return Err(Error::new(ident.span(), "unknown software interrupt")),
+                    // handlers with payload are already dispatched once per message
+                    Some(swi) if swi.payload.is_some() => {
+                        return Err(Error::new(
+                            ident.span(),
+                            "software interrupts with payload cannot be counting",
+                        ))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // shared resources must be used by software interrupts
+        if let Some(resources) = &resources {
+            for (i, resource) in resources.iter().enumerate() {
+                if resources[..i].iter().any(|r| r.ident == resource.ident) {
+                    return Err(Error::new(resource.ident.span(), "duplicate resource"));
+                }
+                for ident in resource.users.iter() {
+                    if !swi_handlers.iter().any(|swi| swi.ident == *ident) {
+                        return Err(Error::new(ident.span(), "unknown software interrupt"));
+                    }
+                }
+            }
+        }
+
+        // static priorities must be valid priority levels
+        if let Some(priorities) = priorities {
+            for swi in swi_handlers.iter() {
+                if swi.priority > priorities {
+                    return Err(Error::new(swi.ident.span(), "priority out of range"));
+                }
+            }
+        }
+
         Ok(CodegenInput {
             slic,
             pac: pac.ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
-            swi_handlers: swi_handlers
-                .ok_or_else(|| Error::new(input.span(), "missing identifier"))?,
+            swi_handlers,
+            counting,
+            priorities,
+            resources,
+            out_of_range: out_of_range.unwrap_or(OutOfRangeInput::Error),
+            tie_break: tie_break.unwrap_or(TieBreakInput::Fifo),
             backend,
         })
     }
@@ -3,20 +3,26 @@ use quote::quote;
 
 mod api;
 mod export;
+mod handler;
 mod input;
+mod resources;
 mod swi;
 
-// Ex. codegen!(pac = <pac crate>, swi = [list, of, software, interrupts], backend = <backend-specific configuration>)
+// Ex. codegen!(pac = <pac crate>, swi = [list, of = 1, software: <payload>; <depth> = 2, interrupts], counting = [list, of, counting, interrupts], priorities = <max priority>, resources = [NAME: <type> = <init> => [list, of, users]], out_of_range = <ignore | error | fault(<hook>)>, backend = <backend-specific configuration>)
 #[proc_macro]
 pub fn codegen(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as input::CodegenInput);
     let slic = &input.slic;
     let pac = &input.pac;
 
-    let api_code = api::api_mod();
+    let api_code = api::api_mod(&input);
 
     let swi_export = export::export_quote(&input);
     let swi_code = swi::swi_mod(&input);
+    let resources_code = match resources::resources_mod(&input) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     quote! {
         /// The RISC-V SLIC module
@@ -28,7 +34,32 @@ pub fn codegen(input: TokenStream) -> TokenStream {
 
             #swi_export
             #swi_code
+            #resources_code
         }
     }
     .into()
 }
+
+/// Attribute for declaring a software interrupt handler.
+///
+/// The argument must be a software interrupt declared in the `codegen!` macro,
+/// which must be invoked in the root of the crate. Handlers must have the `fn()` signature,
+/// or the `fn(<payload>)` signature for software interrupts with a typed payload.
+/// Handlers of software interrupts that use shared resources take their context
+/// (e.g., `slic::resources::context::SoftLow`) as their first argument.
+///
+/// # Example
+///
+/// ```ignore
+/// #[riscv_slic::handler(SoftLow)]
+/// fn soft_low() {
+///     // handle software interrupt
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    let interrupt = syn::parse_macro_input!(args as syn::Ident);
+    let f = syn::parse_macro_input!(input as syn::ItemFn);
+
+    handler::handler(interrupt, f).into()
+}
@@ -0,0 +1,167 @@
+use crate::input::{CodegenInput, ResourceInput};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident, Result};
+
+/// Returns the software interrupts that use at least one shared resource.
+pub fn users(input: &CodegenInput) -> Vec<&Ident> {
+    let resources = match &input.resources {
+        Some(resources) => resources,
+        None => return Vec::new(),
+    };
+    input
+        .swi_handlers
+        .iter()
+        .map(|swi| &swi.ident)
+        .filter(|ident| resources.iter().any(|r| r.users.contains(*ident)))
+        .collect()
+}
+
+/// Returns the ceiling of a shared resource, which is the highest static priority of its users.
+fn ceiling(input: &CodegenInput, resource: &ResourceInput) -> Result<u8> {
+    let mut ceiling = 0;
+    for user in resource.users.iter() {
+        // the user is a software interrupt, as checked when parsing the input
+        let swi = input.swi_handlers.iter().find(|swi| swi.ident == *user);
+        let priority = swi.map(|swi| swi.priority).unwrap_or_default();
+        if priority == 0 {
+            return Err(Error::new(
+                user.span(),
+                "users of shared resources must have a static priority",
+            ));
+        }
+        ceiling = ceiling.max(priority);
+    }
+    Ok(ceiling)
+}
+
+/// Returns the highest priority that each software interrupt can take at runtime.
+/// It is the lowest ceiling of the shared resources that the software interrupt uses
+/// (or 255 if it does not use any shared resource).
+pub fn priority_limits(input: &CodegenInput) -> Vec<u8> {
+    input
+        .swi_handlers
+        .iter()
+        .map(|swi| match &input.resources {
+            Some(resources) => resources
+                .iter()
+                .filter(|r| r.users.contains(&swi.ident))
+                .map(|r| ceiling(input, r).unwrap_or(u8::MAX))
+                .min()
+                .unwrap_or(u8::MAX),
+            None => u8::MAX,
+        })
+        .collect()
+}
+
+/// Creates the module with the shared resources of the SLIC.
+///
+/// Each resource can only be locked with the context of thread mode or of one of its users.
+pub fn resources_mod(input: &CodegenInput) -> Result<TokenStream> {
+    let resources = match &input.resources {
+        Some(resources) => resources,
+        None => return Ok(quote!()),
+    };
+
+    let mut res = Vec::new();
+    for resource in resources.iter() {
+        let ceiling = ceiling(input, resource)?;
+        let contexts = resource.users.iter();
+
+        let ident = &resource.ident;
+        let ty = &resource.ty;
+        let init = &resource.init;
+        let doc = format!(" Proxy of the `{ident}` shared resource.");
+
+        res.push(quote!(
+            #[doc = #doc]
+            #[allow(non_camel_case_types)]
+            pub struct #ident;
+
+            impl Uses<#ident> for context::Thread {}
+            #(impl Uses<#ident> for context::#contexts {})*
+
+            impl #ident {
+                /// Priority ceiling of the shared resource.
+                pub const CEILING: u8 = #ceiling;
+
+                /// Runs a function with exclusive access to the shared resource.
+                /// This function returns the return value of the target function.
+                ///
+                /// Only thread mode and the declared users of the resource can lock it.
+                ///
+                /// # Panics
+                ///
+                /// This function panics if the target function locks the resource again.
+                #[inline]
+                pub fn lock<C: Uses<Self>, R>(_cx: &C, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    static DATA: riscv_slic::PriorityMutex<#ty, #ceiling> =
+                        riscv_slic::PriorityMutex::new(#init);
+                    DATA.lock(f)
+                }
+            }
+        ));
+    }
+
+    let users = users(input);
+    let docs = users
+        .iter()
+        .map(|ident| format!(" Context of the `{ident}` software interrupt handler."));
+
+    Ok(quote!(
+        /// Shared resources of the SLIC.
+        pub mod resources {
+            /// Marker trait for the execution contexts that can lock a shared resource.
+            pub trait Uses<R> {}
+
+            /// Execution contexts that can lock shared resources.
+            ///
+            /// Handlers of software interrupts that use shared resources take their context
+            /// as their first argument. Contexts cannot leave their execution context.
+            pub mod context {
+                /// Context of thread mode. Thread mode can lock all the shared resources.
+                pub struct Thread {
+                    _not_send: core::marker::PhantomData<*const ()>,
+                }
+
+                impl Thread {
+                    /// Creates the context of thread mode.
+                    ///
+                    /// # Safety
+                    ///
+                    /// This function must only be called in thread mode, not in interrupt handlers.
+                    #[inline]
+                    pub unsafe fn steal() -> Self {
+                        Self {
+                            _not_send: core::marker::PhantomData,
+                        }
+                    }
+                }
+
+                #(
+                    #[doc = #docs]
+                    pub struct #users {
+                        _not_send: core::marker::PhantomData<*const ()>,
+                    }
+
+                    impl #users {
+                        /// Creates the context of the software interrupt handler.
+                        ///
+                        /// # Safety
+                        ///
+                        /// This function is only for `riscv-slic` internal use. Do not call it directly.
+                        #[doc(hidden)]
+                        #[inline]
+                        pub unsafe fn steal() -> Self {
+                            Self {
+                                _not_send: core::marker::PhantomData,
+                            }
+                        }
+                    }
+                )*
+            }
+
+            #(#res)*
+        }
+    ))
+}
@@ -1,10 +1,11 @@
 use crate::input::CodegenInput;
+#[cfg(not(feature = "clic-backend"))]
+use crate::input::TieBreakInput;
 use proc_macro2::{Ident, TokenStream};
-use quote::quote;
-use syn::Path;
+use quote::{format_ident, quote};
 
 /// Helper function for generating the interrupt enums. It assigns a number to each source.
-fn interrupts_enum(input: &[Ident]) -> Vec<TokenStream> {
+fn interrupts_enum(input: &[&Ident]) -> Vec<TokenStream> {
     input
         .iter()
         .enumerate()
@@ -12,7 +13,17 @@ fn interrupts_enum(input: &[Ident]) -> Vec<TokenStream> {
         .collect()
 }
 
-fn swi_handler_attribute(pac: &Path) -> TokenStream {
+#[cfg(not(feature = "clic-backend"))]
+#[cfg_attr(
+    any(
+        feature = "sim-backend",
+        feature = "sim-sbi-backend",
+        feature = "imsic-backend"
+    ),
+    allow(unused_variables)
+)]
+fn swi_handler_attribute(input: &CodegenInput) -> TokenStream {
+    let pac = &input.pac;
     match () {
         #[cfg(feature = "mecall-backend")]
         () => quote! {
@@ -26,17 +37,33 @@ fn swi_handler_attribute(pac: &Path) -> TokenStream {
         () => quote! {
             #[riscv_rt::core_interrupt(#pac::interrupt::CoreInterrupt::SupervisorSoft)]
         },
+        #[cfg(feature = "plic-backend")]
+        () => quote! {
+            #[riscv_rt::core_interrupt(#pac::interrupt::CoreInterrupt::MachineExternal)]
+        },
+        #[cfg(feature = "imsic-backend")]
+        () => crate::export::swi_handler_attribute(input),
+        #[cfg(any(feature = "sim-backend", feature = "sim-sbi-backend"))]
+        () => quote! {
+            #[export_name = "__riscv_slic_swi_handler"]
+        },
     }
 }
 
+/// Helper function for generating the priority enum. It includes all the levels from 0 to `max`.
+fn priorities_enum(max: u8) -> Vec<TokenStream> {
+    (0..=max)
+        .map(|i| format!("P{i} = {i}").parse().unwrap())
+        .collect()
+}
+
 /// Creates the SLIC module with the proper interrupt sources.
 pub fn swi_mod(input: &CodegenInput) -> TokenStream {
     let mut res = Vec::new();
 
-    let swi_handlers = &input.swi_handlers;
+    let swi_handlers: Vec<_> = input.swi_handlers.iter().map(|swi| &swi.ident).collect();
     let n_interrupts = swi_handlers.len();
-    let swi_enums = interrupts_enum(swi_handlers);
-    let swi_handler_attribute = swi_handler_attribute(&input.pac);
+    let swi_enums = interrupts_enum(&swi_handlers);
 
     if n_interrupts > 0 {
         res.push(quote!(
@@ -65,20 +92,203 @@ pub fn swi_mod(input: &CodegenInput) -> TokenStream {
                     }
                 }
             }
-
-            extern "C" {
-                #(fn #swi_handlers ();)*
-            }
         ));
     }
+    // handlers of software interrupts that use shared resources take their context
+    let users = crate::resources::users(input);
+    let context = |ident: &Ident| match users.contains(&ident) {
+        true => quote!(crate::slic::resources::context::#ident,),
+        false => quote!(),
+    };
+    let steal = |ident: &Ident| match users.contains(&ident) {
+        true => quote!(unsafe { crate::slic::resources::context::#ident::steal() },),
+        false => quote!(),
+    };
+    // software interrupts without payload nor context are dispatched directly
+    let plain: Vec<_> = input
+        .swi_handlers
+        .iter()
+        .filter(|swi| swi.payload.is_none() && !users.contains(&&swi.ident))
+        .map(|swi| &swi.ident)
+        .collect();
+    // software interrupts without payload that use shared resources are dispatched via a trampoline
+    let with_context: Vec<_> = input
+        .swi_handlers
+        .iter()
+        .filter(|swi| swi.payload.is_none() && users.contains(&&swi.ident))
+        .map(|swi| &swi.ident)
+        .collect();
+    let context_trampolines: Vec<_> = with_context
+        .iter()
+        .map(|ident| format_ident!("__riscv_slic_trampoline_{}", ident))
+        .collect();
+    // software interrupts with payload are dispatched via a trampoline that drains their queue
+    let payloads: Vec<_> = input
+        .swi_handlers
+        .iter()
+        .filter_map(|swi| swi.payload.as_ref().map(|payload| (&swi.ident, payload)))
+        .collect();
+    let payload_idents: Vec<_> = payloads.iter().map(|(ident, _)| *ident).collect();
+    let payload_types: Vec<_> = payloads.iter().map(|(_, (ty, _))| ty).collect();
+    let payload_depths = payloads.iter().map(|(_, (_, depth))| depth);
+    let payload_docs = payload_idents
+        .iter()
+        .map(|ident| format!(" Marker of the `{ident}` software interrupt for sending messages."));
+    let queues: Vec<_> = payload_idents
+        .iter()
+        .map(|ident| format_ident!("__RISCV_SLIC_QUEUE_{}", ident))
+        .collect();
+    let trampolines: Vec<_> = payload_idents
+        .iter()
+        .map(|ident| format_ident!("__riscv_slic_trampoline_{}", ident))
+        .collect();
+    let payload_contexts: Vec<_> = payload_idents.iter().map(|ident| context(ident)).collect();
+    let payload_steals: Vec<_> = payload_idents.iter().map(|ident| steal(ident)).collect();
+    let vector = input
+        .swi_handlers
+        .iter()
+        .map(|swi| match plain.contains(&&swi.ident) {
+            true => swi.ident.clone(),
+            false => format_ident!("__riscv_slic_trampoline_{}", swi.ident),
+        });
     res.push(quote!(
+        extern "C" {
+            #(fn #plain ();)*
+        }
+
+        extern "Rust" {
+            #(fn #with_context (cx: crate::slic::resources::context::#with_context);)*
+            #(fn #payload_idents (#payload_contexts message: #payload_types);)*
+        }
+
+        /// Signatures of the software interrupt handlers (used for checking handlers).
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        pub mod __handlers {
+            #(pub type #plain = unsafe extern "C" fn();)*
+            #(pub type #with_context = fn(crate::slic::resources::context::#with_context);)*
+            #(pub type #payload_idents = fn(#payload_contexts #payload_types);)*
+        }
+
+        /// Markers of software interrupts with typed payloads.
+        pub mod payload {
+            #(
+                #[doc = #payload_docs]
+                #[derive(Clone, Copy, Debug)]
+                pub struct #payload_idents;
+
+                unsafe impl riscv_slic::PayloadInterrupt for #payload_idents {
+                    type Interrupt = super::SoftwareInterrupt;
+                    type Payload = #payload_types;
+
+                    #[inline]
+                    fn interrupt(self) -> Self::Interrupt {
+                        super::SoftwareInterrupt::#payload_idents
+                    }
+
+                    #[inline]
+                    fn enqueue(self, payload: Self::Payload) -> Result<(), Self::Payload> {
+                        super::#queues.enqueue(payload)
+                    }
+                }
+            )*
+        }
+
+        #(
+            /// Message queue of a software interrupt with payload.
+            #[allow(non_upper_case_globals)]
+            static #queues: riscv_slic::MessageQueue<#payload_types, #payload_depths> =
+                riscv_slic::MessageQueue::new();
+
+            /// Dispatches the handler of a software interrupt once per message in its queue.
+            #[allow(non_snake_case)]
+            unsafe extern "C" fn #trampolines() {
+                // SAFETY: the trampoline is the only consumer of the queue,
+                // and the context is only created for the handler of the software interrupt
+                while let Some(message) = unsafe { #queues.dequeue() } {
+                    #payload_idents(#payload_steals message);
+                }
+            }
+        )*
+
+        #(
+            /// Dispatches the handler of a software interrupt with its context.
+            #[allow(non_snake_case)]
+            unsafe extern "C" fn #context_trampolines() {
+                // SAFETY: the context is only created for the handler of the software interrupt
+                #with_context(unsafe { crate::slic::resources::context::#with_context::steal() });
+            }
+        )*
+    ));
+
+    #[cfg(not(feature = "clic-backend"))]
+    let max_priority = input.priorities.unwrap_or(u8::MAX);
+    // the CLIC backend only supports the priorities that fit in its level bits
+    #[cfg(feature = "clic-backend")]
+    let max_priority = crate::export::max_priority(input);
+    let priority_enums = priorities_enum(max_priority);
+    res.push(quote!(
+        /// Valid priority levels of the SLIC. Level 0 disables a software interrupt.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+        #[repr(u8)]
+        pub enum Priority {
+            #(#priority_enums),*
+        }
+
+        unsafe impl riscv_slic::PriorityNumber for Priority {
+            const MAX_PRIORITY_NUMBER: u8 = #max_priority;
+
+            #[inline]
+            fn number(self) -> u8 {
+                self as u8
+            }
+
+            #[inline]
+            fn from_number(value: u8) -> Result<Self, u8> {
+                if value > Self::MAX_PRIORITY_NUMBER {
+                    Err(value)
+                } else {
+                    // SAFETY: the value is less than the maximum priority number
+                    Ok(unsafe { core::mem::transmute(value) })
+                }
+            }
+        }
+    ));
+    let priority_limits = crate::resources::priority_limits(input);
+    res.push(quote!(
+        /// Highest valid priority level of the application.
+        const __MAX_PRIORITY: u8 = #max_priority;
+
+        /// Highest priority level of each software interrupt, so it does not
+        /// exceed the ceilings of the shared resources that it uses.
+        const __PRIORITY_LIMITS: [u8; #n_interrupts] = [#(#priority_limits),*];
+
         /// Array of software interrupt handlers in the order of the `Interrupt` enum.
         static __SOFTWARE_INTERRUPTS: [unsafe extern "C" fn(); #n_interrupts] = [
-            #(#swi_handlers),*
+            #(#vector),*
         ];
+    ));
+    res.push(slic_instance(input));
+    quote!(#(#res)*)
+}
 
+/// Creates the static SLIC instance and the software interrupt handler that dispatches its interrupts.
+#[cfg(not(feature = "clic-backend"))]
+fn slic_instance(input: &CodegenInput) -> TokenStream {
+    let n_interrupts = input.swi_handlers.len();
+    let swi_handler_attribute = swi_handler_attribute(input);
+    let priorities = input.swi_handlers.iter().map(|swi| swi.priority);
+    let counting = input.swi_handlers.iter().map(|swi| match &input.counting {
+        Some(counting) => counting.contains(&swi.ident),
+        None => false,
+    });
+    let tie_break = match input.tie_break {
+        TieBreakInput::Fifo => quote!(riscv_slic::Fifo),
+        TieBreakInput::LowestNumberFirst => quote!(riscv_slic::LowestNumberFirst),
+    };
+    quote!(
         /// The static SLIC instance
-        static mut __SLIC: riscv_slic::MutexSLIC<#n_interrupts> = riscv_slic::new_slic();
+        static mut __SLIC: riscv_slic::MutexSLIC<#n_interrupts, #tie_break> = riscv_slic::new_slic([#(#priorities),*], [#(#counting),*]);
 
         /// Software interrupt handler to be used with the SLIC.
         #swi_handler_attribute
@@ -87,6 +297,16 @@ pub fn swi_mod(input: &CodegenInput) -> TokenStream {
             // We nest the handler to let other interrupts trigger
             riscv_slic::nested(|| unsafe { __riscv_slic_pop() });
         }
-    ));
-    quote!(#(#res)*)
+    )
+}
+
+/// Creates the static SLIC instance on top of the CLIC, which dispatches software interrupts in hardware.
+#[cfg(feature = "clic-backend")]
+fn slic_instance(input: &CodegenInput) -> TokenStream {
+    let n_interrupts = input.swi_handlers.len();
+    let new_slic = crate::export::new_slic(input);
+    quote!(
+        /// The static SLIC instance
+        static mut __SLIC: riscv_slic::MutexSLIC<#n_interrupts> = #new_slic;
+    )
 }
@@ -1,22 +1,44 @@
+#[cfg(feature = "sim")]
+pub use crate::sim::nested;
+#[cfg(not(feature = "sim"))]
 pub use riscv::interrupt::nested;
 
+#[cfg(feature = "sim")]
+use crate::sim::{
+    disable as disable_interrupts, disable_swi, enable as enable_interrupts, enable_swi,
+};
+#[cfg(not(feature = "sim"))]
 use riscv::interrupt::{disable as disable_interrupts, enable as enable_interrupts};
 #[cfg(feature = "msoft")]
 use riscv::register::mie::{clear_msoft as disable_swi, set_msoft as enable_swi};
 #[cfg(feature = "ssoft")]
 use riscv::register::sie::{clear_ssoft as disable_swi, set_ssoft as enable_swi};
+#[cfg(feature = "swi-hooks")]
+use {__riscv_slic_swi_disable as disable_swi, __riscv_slic_swi_enable as enable_swi};
 
 extern "Rust" {
-    #[cfg(any(feature = "msoft", feature = "ssoft"))]
+    #[cfg(any(feature = "msoft", feature = "ssoft", feature = "sim"))]
     fn __riscv_slic_swi_unpend();
+    #[cfg(feature = "swi-hooks")]
+    fn __riscv_slic_swi_enable();
+    #[cfg(feature = "swi-hooks")]
+    fn __riscv_slic_swi_disable();
     fn __riscv_slic_enable();
     fn __riscv_slic_disable();
     fn __riscv_slic_get_threshold() -> u8;
     fn __riscv_slic_set_threshold(priority: u8);
-    fn __riscv_slic_raise_threshold(priority: u8) -> Result<u8, ()>;
-    fn __riscv_slic_get_priority(interrupt: u16) -> u8;
-    fn __riscv_slic_set_priority(interrupt: u16, priority: u8);
-    fn __riscv_slic_pend(interrupt: u16);
+    fn __riscv_slic_raise_threshold(priority: u8) -> Result<u8, crate::Error>;
+    fn __riscv_slic_check_interrupt(interrupt: u16) -> Result<(), crate::Error>;
+    fn __riscv_slic_check_priority(priority: u8) -> Result<(), crate::Error>;
+    fn __riscv_slic_get_priority(interrupt: u16) -> Result<u8, crate::Error>;
+    fn __riscv_slic_set_priority(interrupt: u16, priority: u8) -> Result<(), crate::Error>;
+    fn __riscv_slic_is_interrupt_enabled(interrupt: u16) -> Result<bool, crate::Error>;
+    fn __riscv_slic_enable_interrupt(interrupt: u16) -> Result<(), crate::Error>;
+    fn __riscv_slic_disable_interrupt(interrupt: u16) -> Result<(), crate::Error>;
+    fn __riscv_slic_pend(interrupt: u16) -> Result<(), crate::Error>;
+    fn __riscv_slic_unpend(interrupt: u16) -> Result<bool, crate::Error>;
+    fn __riscv_slic_is_pending(interrupt: u16) -> Result<bool, crate::Error>;
+    fn __riscv_slic_is_running(interrupt: u16) -> Result<bool, crate::Error>;
 }
 
 /// Enables the SLIC, software interrupts (if needed), and system interrupts.
@@ -27,7 +49,12 @@ extern "Rust" {
 #[inline]
 pub unsafe fn enable() {
     __riscv_slic_enable();
-    #[cfg(any(feature = "msoft", feature = "ssoft"))]
+    #[cfg(any(
+        feature = "msoft",
+        feature = "ssoft",
+        feature = "sim",
+        feature = "swi-hooks"
+    ))]
     enable_swi();
     enable_interrupts();
 }
@@ -36,7 +63,12 @@ pub unsafe fn enable() {
 #[inline]
 pub fn disable() {
     disable_interrupts();
-    #[cfg(any(feature = "msoft", feature = "ssoft"))]
+    #[cfg(any(
+        feature = "msoft",
+        feature = "ssoft",
+        feature = "sim",
+        feature = "swi-hooks"
+    ))]
     // SAFETY: it is safe to clear software interrupt flags
     unsafe {
         disable_swi();
@@ -59,39 +91,245 @@ pub fn get_threshold() -> u8 {
 ///
 /// Setting the priority threshold to a value lower than the current may lead to priority inversion.
 #[inline]
-pub unsafe fn set_threshold(priority: u8) {
+pub unsafe fn set_threshold<P: crate::PriorityNumber>(priority: P) {
+    __riscv_slic_set_threshold(priority.number());
+}
+
+/// Stabilized API for setting the threshold of the SLIC.
+/// Returns an error if the threshold is higher than the maximum priority level of the application.
+///
+/// # Safety
+///
+/// Setting the priority threshold to a value lower than the current may lead to priority inversion.
+#[inline]
+pub unsafe fn try_set_threshold<P: crate::PriorityNumber>(priority: P) -> Result<(), crate::Error> {
+    let priority = priority.number();
+    __riscv_slic_check_priority(priority)?;
     __riscv_slic_set_threshold(priority);
+    Ok(())
+}
+
+/// Stabilized API for getting the priority of a software interrupt of the SLIC.
+#[inline]
+pub fn get_priority<I: crate::InterruptNumber>(interrupt: I) -> u8 {
+    // SAFETY: this read has no side effects.
+    unsafe { __riscv_slic_get_priority(interrupt.number()) }.unwrap_or_default()
 }
 
 /// Stabilized API for setting the priority of a software interrupt of the SLIC.
 ///
+/// If the software interrupt is already pending, the new priority takes effect immediately.
+/// Setting the priority of a pending software interrupt to 0 cancels it.
+/// Software interrupts that use shared resources cannot take priorities above the ceilings
+/// of their resources. These requests are ignored (see [`try_set_priority`]).
+///
 /// # Safety
 ///
 /// Changing the priority of an interrupt may break mask-based critical sections.
 #[inline]
-pub unsafe fn set_priority<I: crate::InterruptNumber>(interrupt: I, priority: u8) {
-    __riscv_slic_set_priority(interrupt.number(), priority);
+pub unsafe fn set_priority<I: crate::InterruptNumber, P: crate::PriorityNumber>(
+    interrupt: I,
+    priority: P,
+) {
+    // out-of-range interrupt numbers are handled by the out-of-range policy
+    let _ = __riscv_slic_set_priority(interrupt.number(), priority.number());
+}
+
+/// Stabilized API for setting the priority of a software interrupt of the SLIC.
+/// Returns an error if the interrupt or the priority are not valid for the application,
+/// or if the priority is higher than the ceiling of a shared resource used by the interrupt.
+///
+/// # Safety
+///
+/// Changing the priority of an interrupt may break mask-based critical sections.
+#[inline]
+pub unsafe fn try_set_priority<I: crate::InterruptNumber, P: crate::PriorityNumber>(
+    interrupt: I,
+    priority: P,
+) -> Result<(), crate::Error> {
+    let (interrupt, priority) = (interrupt.number(), priority.number());
+    __riscv_slic_check_interrupt(interrupt)?;
+    __riscv_slic_check_priority(priority)?;
+    __riscv_slic_set_priority(interrupt, priority)
+}
+
+/// Stabilized API for checking if a software interrupt of the SLIC is enabled.
+#[inline]
+pub fn is_interrupt_enabled<I: crate::InterruptNumber>(interrupt: I) -> bool {
+    // SAFETY: this read has no side effects.
+    unsafe { __riscv_slic_is_interrupt_enabled(interrupt.number()) }.unwrap_or_default()
+}
+
+/// Stabilized API for enabling a software interrupt of the SLIC.
+///
+/// If the software interrupt was pended while disabled, it is triggered now.
+///
+/// # Safety
+///
+/// Enabling an interrupt may break mask-based critical sections.
+#[inline]
+pub unsafe fn enable_interrupt<I: crate::InterruptNumber>(interrupt: I) {
+    // out-of-range interrupt numbers are handled by the out-of-range policy
+    let _ = __riscv_slic_enable_interrupt(interrupt.number());
+}
+
+/// Stabilized API for disabling a software interrupt of the SLIC.
+///
+/// The priority of the software interrupt is preserved. Pending the software
+/// interrupt while disabled is latched until the software interrupt is enabled again.
+#[inline]
+pub fn disable_interrupt<I: crate::InterruptNumber>(interrupt: I) {
+    // SAFETY: it is safe to disable a software interrupt
+    let _ = unsafe { __riscv_slic_disable_interrupt(interrupt.number()) };
 }
 
 /// Stabilized API for pending a software interrupt on the SLIC.
 #[inline]
 pub fn pend<I: crate::InterruptNumber>(interrupt: I) {
     // SAFETY: it is safe to pend a software interrupt
-    unsafe { __riscv_slic_pend(interrupt.number()) };
+    let _ = unsafe { __riscv_slic_pend(interrupt.number()) };
 }
 
-/// Runs a function with priority mask.
+/// Stabilized API for pending a software interrupt on the SLIC.
+/// Returns an error if the interrupt is not valid for the application.
+#[inline]
+pub fn try_pend<I: crate::InterruptNumber>(interrupt: I) -> Result<(), crate::Error> {
+    let interrupt = interrupt.number();
+    // SAFETY: it is safe to pend a software interrupt
+    unsafe {
+        __riscv_slic_check_interrupt(interrupt)?;
+        __riscv_slic_pend(interrupt)
+    }
+}
+
+/// Stabilized API for sending a message to a software interrupt with a typed payload.
+///
+/// The message is enqueued and the software interrupt is pended. Its handler is dispatched
+/// once per message. If the queue of the software interrupt is full, it returns [`Error::QueueFull`].
+/// If the software interrupt has priority 0, it cannot be pended, so the message is not
+/// enqueued and it returns [`Error::ZeroPriority`].
+///
+/// [`Error::QueueFull`]: crate::Error::QueueFull
+/// [`Error::ZeroPriority`]: crate::Error::ZeroPriority
+#[inline]
+pub fn pend_with<S: crate::PayloadInterrupt>(
+    source: S,
+    payload: S::Payload,
+) -> Result<(), crate::Error> {
+    let interrupt = source.interrupt();
+    if get_priority(interrupt) == 0 {
+        return Err(crate::Error::ZeroPriority(crate::InterruptNumber::number(
+            interrupt,
+        )));
+    }
+    source
+        .enqueue(payload)
+        .map_err(|_| crate::Error::QueueFull)?;
+    pend(interrupt);
+    Ok(())
+}
+
+/// Stabilized API for checking if a software interrupt is pending on the SLIC.
+#[inline]
+pub fn is_pending<I: crate::InterruptNumber>(interrupt: I) -> bool {
+    // SAFETY: this read has no side effects.
+    unsafe { __riscv_slic_is_pending(interrupt.number()) }.unwrap_or_default()
+}
+
+/// Stabilized API for checking if the handler of a software interrupt is running.
+/// Handlers preempted by higher priority software interrupts are also considered as running.
+#[inline]
+pub fn is_running<I: crate::InterruptNumber>(interrupt: I) -> bool {
+    // SAFETY: this read has no side effects.
+    unsafe { __riscv_slic_is_running(interrupt.number()) }.unwrap_or_default()
+}
+
+/// Stabilized API for cancelling a pending software interrupt on the SLIC.
+///
+/// If the software interrupt is not pending, this request is silently ignored.
+#[inline]
+pub fn unpend<I: crate::InterruptNumber>(interrupt: I) {
+    take_pending(interrupt);
+}
+
+/// Stabilized API for cancelling a pending software interrupt on the SLIC.
+/// Returns `true` if the software interrupt was pending.
+#[inline]
+pub fn take_pending<I: crate::InterruptNumber>(interrupt: I) -> bool {
+    // SAFETY: it is safe to unpend a software interrupt
+    unsafe { __riscv_slic_unpend(interrupt.number()) }.unwrap_or_default()
+}
+
+/// Guard that restores the previous threshold of the SLIC when dropped.
+///
+/// Guards must be dropped in the reverse order of their creation. In debug builds,
+/// dropping a guard while a more recent guard is still alive causes a panic.
+/// Guards cannot be sent to other execution contexts.
+#[must_use = "the threshold is restored as soon as the guard is dropped"]
+pub struct ThresholdGuard {
+    /// The threshold to restore and the raised threshold (if the threshold was raised)
+    state: Option<(u8, u8)>,
+    /// Guards must not leave the execution context in which they were created
+    _not_send: core::marker::PhantomData<*const ()>,
+}
+
+impl ThresholdGuard {
+    /// Returns `true` if the threshold was raised when the guard was created.
+    #[inline]
+    pub fn is_raised(&self) -> bool {
+        self.state.is_some()
+    }
+}
+
+impl Drop for ThresholdGuard {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((previous, raised)) = self.state {
+            debug_assert_eq!(
+                get_threshold(),
+                raised,
+                "threshold guards must be dropped in LIFO order"
+            );
+            // SAFETY: we restore the threshold that was active when the guard was created
+            unsafe { __riscv_slic_set_threshold(previous) };
+        }
+    }
+}
+
+/// Raises the threshold of the SLIC until the returned guard is dropped.
+///
+/// If the current threshold is greater than or equal to `priority`, the threshold
+/// is not modified and dropping the guard has no effect.
+#[inline]
+pub fn raise<P: crate::PriorityNumber>(priority: P) -> ThresholdGuard {
+    raise_level(priority.number())
+}
+
+/// Raises the threshold of the SLIC to a raw priority level until the returned guard is dropped.
 #[inline]
-pub fn run<F: FnOnce()>(priority: u8, f: F) {
-    // SAFETY: we restore the previous threshold after the function is done
+pub(crate) fn raise_level(priority: u8) -> ThresholdGuard {
+    // SAFETY: the guard restores the previous threshold when dropped
     let previous = unsafe { __riscv_slic_raise_threshold(priority) };
-    f();
-    if let Ok(prev) = previous {
-        // SAFETY: we restore the previous threshold after the function is done
-        unsafe { __riscv_slic_set_threshold(prev) };
+    ThresholdGuard {
+        state: previous.ok().map(|previous| (previous, priority)),
+        _not_send: core::marker::PhantomData,
     }
 }
 
+/// Runs a function with priority mask.
+#[inline]
+pub fn run<P: crate::PriorityNumber, F: FnOnce()>(priority: P, f: F) {
+    let _guard = raise(priority);
+    f();
+}
+
+/// Returns `true` if a raw priority level is valid for the application.
+#[inline]
+pub(crate) fn is_valid_priority(priority: u8) -> bool {
+    // SAFETY: this check has no side effects.
+    unsafe { __riscv_slic_check_priority(priority) }.is_ok()
+}
+
 /// Runs a function that takes a shared resource with a priority ceiling.
 /// This function returns the return value of the target function.
 ///
@@ -99,17 +337,12 @@ pub fn run<F: FnOnce()>(priority: u8, f: F) {
 ///
 /// Input argument `ptr` must be a valid pointer to a shared resource.
 #[inline]
-pub unsafe fn lock<F, T, R>(ptr: *mut T, ceiling: u8, f: F) -> R
+pub unsafe fn lock<F, T, R, P>(ptr: *mut T, ceiling: P, f: F) -> R
 where
     F: FnOnce(&mut T) -> R,
+    P: crate::PriorityNumber,
 {
-    // SAFETY: we restore the previous threshold after the function is done
-    let previous = unsafe { __riscv_slic_raise_threshold(ceiling) };
+    let _guard = raise(ceiling);
     // SAFETY: provided that caller respects the safety requirements, this is safe
-    let r = f(&mut *ptr);
-    if let Ok(prev) = previous {
-        // SAFETY: we restore the previous threshold after the function is done
-        unsafe { __riscv_slic_set_threshold(prev) };
-    }
-    r
+    f(&mut *ptr)
 }
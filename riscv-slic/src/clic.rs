@@ -0,0 +1,729 @@
+//! Software interrupt controller on top of the dedicated software interrupt lines of a CLIC.
+//!
+//! Each software interrupt source is assigned a CLIC line. The priorities of the software
+//! interrupts are programmed as CLIC levels in the `clicintctl` registers, and the threshold
+//! lives in the `mintthresh` register. Thus, the CLIC dispatches software interrupts in hardware.
+//!
+//! The lines of software interrupts are hardware vectored. The `__riscv_slic_clic_int` and
+//! `__riscv_slic_clic_vectors` hooks are generated by the [`riscv_slic_macros::codegen`] macro.
+
+use crate::Error;
+
+/// Offset of the interrupt pending register (`clicintip`) of a CLIC line.
+const CLICINTIP: usize = 0;
+/// Offset of the interrupt enable register (`clicintie`) of a CLIC line.
+const CLICINTIE: usize = 1;
+/// Offset of the interrupt attribute register (`clicintattr`) of a CLIC line.
+const CLICINTATTR: usize = 2;
+/// Offset of the interrupt control register (`clicintctl`) of a CLIC line.
+const CLICINTCTL: usize = 3;
+/// Attributes of the CLIC lines: machine mode, positive edge-triggered, and hardware vectored.
+const CLICINTATTR_VALUE: u8 = 0b1100_0011;
+
+extern "Rust" {
+    fn __riscv_slic_clic_int(interrupt: u16) -> *mut u8;
+    fn __riscv_slic_clic_vectors() -> usize;
+}
+
+#[cfg(feature = "clic-backend")]
+#[doc(hidden)]
+pub type MutexSLIC<const N: usize> = critical_section::Mutex<core::cell::RefCell<CLIC<N>>>;
+
+#[cfg(feature = "clic-backend")]
+#[doc(hidden)]
+#[inline]
+pub const fn new_slic<const N: usize>(
+    level_bits: u8,
+    priorities: [u8; N],
+    counting: [bool; N],
+) -> MutexSLIC<N> {
+    critical_section::Mutex::new(core::cell::RefCell::new(CLIC::new(
+        level_bits, priorities, counting,
+    )))
+}
+
+/// Returns a pointer to a register of the CLIC line of a given interrupt source.
+#[inline]
+unsafe fn reg(interrupt: u16, offset: usize) -> *mut u8 {
+    __riscv_slic_clic_int(interrupt).add(offset)
+}
+
+/// Software interrupt controller on top of the CLIC.
+///
+/// The vector table and the CLIC lines are configured the first time that the controller
+/// is enabled. Pend requests are latched in the controller while it is disabled, and the
+/// `clicintip` registers of the latched interrupt sources are set when it is enabled.
+///
+/// # Priority levels
+///
+/// The CLIC only implements `level_bits` bits of the interrupt levels. Thus, the valid
+/// priorities range from 0 to `2^level_bits - 1`. The unused bits of the CLIC levels are
+/// set to 1, so software interrupts with priority 0 never interrupt with threshold 0.
+///
+/// # Counting mode
+///
+/// The `clicintip` register of a CLIC line only holds one pend request. Interrupt sources
+/// in counting mode keep track of their pend requests in the controller instead, and
+/// their `clicintip` register is set again when their handler completes.
+/// The counter saturates at `u16::MAX` pend requests.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
+pub struct CLIC<const N: usize> {
+    /// Unused bits of the interrupt levels (i.e., `8 - level_bits`).
+    shift: u32,
+    /// Initialization flag. If `true`, the vector table and the CLIC lines are already configured.
+    initialized: bool,
+    /// Enable flag. If `false`, pend requests are only latched in the controller.
+    enabled: bool,
+    /// Array with the priorities assigned to each software interrupt source.
+    priorities: [u8; N],
+    /// Array to check if a software interrupt source is enabled.
+    enables: [bool; N],
+    /// Array to check if a software interrupt source is in counting mode.
+    counting: [bool; N],
+    /// Array to check if a software interrupt source is pending.
+    pending: [bool; N],
+    /// Array with the number of pend requests of each software interrupt source in counting mode.
+    counts: [u16; N],
+    /// Array to check if the handler of a software interrupt source is running.
+    running: [bool; N],
+}
+
+impl<const N: usize> CLIC<N> {
+    /// Creates a new software interrupt controller for a CLIC with `level_bits` level bits.
+    /// The `priorities` array contains the initial priority of each interrupt source.
+    /// The `counting` array indicates which interrupt sources are in counting mode.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `level_bits` is not between 1 and 8,
+    /// or if an initial priority does not fit in `level_bits` bits.
+    #[inline]
+    const fn new(level_bits: u8, priorities: [u8; N], counting: [bool; N]) -> Self {
+        assert!(
+            1 <= level_bits && level_bits <= 8,
+            "invalid CLIC level bits"
+        );
+        let shift = 8 - level_bits as u32;
+        let mut i = 0;
+        while i < N {
+            assert!(priorities[i] <= u8::MAX >> shift, "priority out of range");
+            i += 1;
+        }
+        Self {
+            shift,
+            initialized: false,
+            enabled: false,
+            priorities,
+            enables: [true; N],
+            counting,
+            pending: [false; N],
+            counts: [0; N],
+            running: [false; N],
+        }
+    }
+
+    /// Returns the highest priority supported by the CLIC.
+    #[inline]
+    pub const fn max_priority(&self) -> u8 {
+        u8::MAX >> self.shift
+    }
+
+    /// Converts a valid priority to an interrupt level of the CLIC.
+    #[inline]
+    const fn level(&self, priority: u8) -> u8 {
+        (priority << self.shift) | ((1 << self.shift) - 1) as u8
+    }
+
+    /// Converts an interrupt level of the CLIC to a priority.
+    #[inline]
+    const fn priority(&self, level: u8) -> u8 {
+        level >> self.shift
+    }
+
+    /// Writes the `clicintip` register of an interrupt source if the CLIC lines are configured.
+    #[inline]
+    fn write_ip(&self, interrupt: u16, pending: bool) {
+        if self.initialized {
+            // SAFETY: the CLIC line of the interrupt source is configured
+            unsafe { reg(interrupt, CLICINTIP).write_volatile(pending as u8) };
+        }
+    }
+
+    /// Configures the vector table of the CLIC and the lines of the software interrupt sources.
+    /// Only the first call has effect.
+    #[inline]
+    fn init(&mut self) {
+        if self.initialized {
+            return;
+        }
+        // SAFETY: the hooks return the vector table and the lines of the software interrupts
+        unsafe {
+            csr::write_mtvt(__riscv_slic_clic_vectors());
+            csr::write_mtvec_clic();
+            for (i, priority) in self.priorities.into_iter().enumerate() {
+                let interrupt = i as u16;
+                reg(interrupt, CLICINTIE).write_volatile(0);
+                reg(interrupt, CLICINTATTR).write_volatile(CLICINTATTR_VALUE);
+                reg(interrupt, CLICINTCTL).write_volatile(self.level(priority));
+                reg(interrupt, CLICINTIP).write_volatile(0);
+                reg(interrupt, CLICINTIE).write_volatile(self.enables[i] as u8);
+            }
+        }
+        self.initialized = true;
+    }
+
+    /// Enables the software interrupt controller and sets the `clicintip`
+    /// registers of the pending interrupt sources.
+    /// The CLIC triggers software interrupts in hardware, so it always returns `false`.
+    #[inline]
+    pub fn enable(&mut self) -> bool {
+        self.init();
+        self.enabled = true;
+        for i in 0..N {
+            if self.pending[i] {
+                self.write_ip(i as u16, true);
+            }
+        }
+        false
+    }
+
+    /// Disables the software interrupt controller and clears the `clicintip` registers
+    /// of the pending interrupt sources. Their pend requests are latched until it is enabled again.
+    #[inline]
+    pub fn disable(&mut self) {
+        self.enabled = false;
+        for i in 0..N {
+            if self.pending[i] {
+                self.write_ip(i as u16, false);
+            }
+        }
+    }
+
+    /// Returns the current priority of an interrupt source.
+    #[inline]
+    pub fn get_priority(&self, interrupt: u16) -> u8 {
+        self.priorities[interrupt as usize]
+    }
+
+    /// Sets the priority of an interrupt source in its `clicintctl` register.
+    /// Setting the priority of a pending interrupt source to 0 cancels it.
+    ///
+    /// # Note
+    ///
+    /// Priorities higher than [`CLIC::max_priority`] are rejected, and the priority is not changed.
+    #[inline]
+    pub fn set_priority(&mut self, interrupt: u16, priority: u8) {
+        if priority > self.max_priority() {
+            return;
+        }
+        let i = interrupt as usize;
+        self.priorities[i] = priority;
+        if priority == 0 && self.pending[i] {
+            self.pending[i] = false;
+            self.counts[i] = 0;
+            self.write_ip(interrupt, false);
+        }
+        if self.initialized {
+            // SAFETY: the CLIC line of the interrupt source is configured
+            unsafe { reg(interrupt, CLICINTCTL).write_volatile(self.level(priority)) };
+        }
+    }
+
+    /// Checks if an interrupt source is enabled.
+    #[inline]
+    pub fn is_interrupt_enabled(&self, interrupt: u16) -> bool {
+        self.enables[interrupt as usize]
+    }
+
+    /// Enables an interrupt source in its `clicintie` register.
+    /// The CLIC triggers software interrupts in hardware, so it always returns `false`.
+    #[inline]
+    pub fn enable_interrupt(&mut self, interrupt: u16) -> bool {
+        self.enables[interrupt as usize] = true;
+        if self.initialized {
+            // SAFETY: the CLIC line of the interrupt source is configured
+            unsafe { reg(interrupt, CLICINTIE).write_volatile(1) };
+        }
+        false
+    }
+
+    /// Disables an interrupt source in its `clicintie` register.
+    #[inline]
+    pub fn disable_interrupt(&mut self, interrupt: u16) {
+        self.enables[interrupt as usize] = false;
+        if self.initialized {
+            // SAFETY: the CLIC line of the interrupt source is configured
+            unsafe { reg(interrupt, CLICINTIE).write_volatile(0) };
+        }
+    }
+
+    /// Returns the current priority threshold, which is the highest level between the
+    /// `mintthresh` register and the level of the running handler (`mintstatus.mil`).
+    #[inline]
+    pub fn get_threshold(&self) -> u8 {
+        // SAFETY: reading the threshold and the interrupt level has no side effects
+        let level = unsafe { csr::read_mintthresh().max(csr::read_mil()) };
+        self.priority(level)
+    }
+
+    /// Sets the priority threshold in the `mintthresh` register.
+    /// Thresholds higher than [`CLIC::max_priority`] mask all the software interrupts.
+    ///
+    /// # Safety
+    ///
+    /// Setting the priority threshold to a value lower than the current threshold
+    /// may lead to priority inversion. If you want to make sure that the threshold
+    /// is raised, use the [`CLIC::raise_threshold`] method instead.
+    #[inline]
+    pub unsafe fn set_threshold(&mut self, priority: u8) {
+        let level = match priority > self.max_priority() {
+            true => u8::MAX,
+            false => self.level(priority),
+        };
+        csr::write_mintthresh(level);
+    }
+
+    /// Sets the priority threshold only to a higher value than the current threshold.
+    /// When the threshold is raised, the function returns `Ok(prev_threshold)`, where
+    /// `prev_threshold` is the previous value of the `mintthresh` register.
+    /// Otherwise, the threshold is not changed and [`Error::ThresholdNotRaised`] is returned.
+    pub fn raise_threshold(&mut self, priority: u8) -> Result<u8, Error> {
+        let current = self.get_threshold();
+        if priority > current {
+            // SAFETY: reading the threshold has no side effects
+            let previous = self.priority(unsafe { csr::read_mintthresh() });
+            // SAFETY: the threshold is raised
+            unsafe { self.set_threshold(priority) };
+            Ok(previous)
+        } else {
+            Err(Error::ThresholdNotRaised { current })
+        }
+    }
+
+    /// Checks that a given interrupt number corresponds to a software interrupt source.
+    #[inline]
+    pub fn check_interrupt(&self, interrupt: u16) -> Result<(), Error> {
+        if (interrupt as usize) < N {
+            Ok(())
+        } else {
+            Err(Error::InvalidInterrupt(interrupt))
+        }
+    }
+
+    /// Checks if a given interrupt is pending.
+    #[inline]
+    pub fn is_pending(&self, interrupt: u16) -> bool {
+        self.pending[interrupt as usize]
+    }
+
+    /// Returns the number of pending requests of a given interrupt.
+    /// For interrupt sources that are not in counting mode, this is at most 1.
+    #[inline]
+    pub fn get_count(&self, interrupt: u16) -> u16 {
+        let i = interrupt as usize;
+        match self.counting[i] {
+            true => self.counts[i],
+            false => self.pending[i] as u16,
+        }
+    }
+
+    /// Checks if the handler of a given interrupt is running.
+    /// Preempted handlers are also considered as running.
+    #[inline]
+    pub fn is_running(&self, interrupt: u16) -> bool {
+        self.running[interrupt as usize]
+    }
+
+    /// The CLIC triggers software interrupts in hardware, so it always returns `false`.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        false
+    }
+
+    /// Sets an interrupt source as pending. If the controller is enabled,
+    /// the pend request is forwarded to its `clicintip` register.
+    ///
+    /// # Notes
+    ///
+    /// If the interrupt priority is 0, this request is silently ignored.
+    /// If the interrupt source is already pending, this request is silently ignored
+    /// unless the interrupt source is in counting mode.
+    #[inline]
+    pub fn pend(&mut self, interrupt: u16) {
+        let i = interrupt as usize;
+        if self.priorities[i] == 0 {
+            return;
+        }
+        if self.counting[i] {
+            self.counts[i] = self.counts[i].saturating_add(1);
+        }
+        self.pending[i] = true;
+        if self.enabled {
+            self.write_ip(interrupt, true);
+        }
+    }
+
+    /// Clears the pending state of an interrupt source and its `clicintip` register.
+    /// For interrupt sources in counting mode, all the pend requests are discarded.
+    /// Returns `true` if the interrupt source was pending.
+    #[inline]
+    pub fn unpend(&mut self, interrupt: u16) -> bool {
+        let i = interrupt as usize;
+        let was_pending = self.pending[i];
+        if was_pending {
+            self.pending[i] = false;
+            self.counts[i] = 0;
+            self.write_ip(interrupt, false);
+        }
+        was_pending
+    }
+
+    /// The CLIC dispatches software interrupts in hardware, so it always returns `None`.
+    #[inline]
+    pub fn pop(&mut self) -> Option<(u8, u16)> {
+        None
+    }
+
+    /// Marks the handler of a given interrupt as running and consumes one of its pend requests.
+    /// It must be called by the trap handler of the CLIC line of the interrupt source.
+    /// Once its handler is done, you must call the [`CLIC::complete`] method.
+    #[inline]
+    pub fn start(&mut self, interrupt: u16) {
+        let i = interrupt as usize;
+        // edge-triggered lines are cleared by hardware vectoring, but we make sure
+        self.write_ip(interrupt, false);
+        if self.counts[i] > 1 {
+            self.counts[i] -= 1;
+        } else {
+            self.counts[i] = 0;
+            self.pending[i] = false;
+        }
+        self.running[i] = true;
+    }
+
+    /// Marks the handler of a given interrupt as done.
+    /// If the interrupt source is still pending (e.g., it has more pend requests in
+    /// counting mode), its `clicintip` register is set again.
+    #[inline]
+    pub fn complete(&mut self, interrupt: u16) {
+        let i = interrupt as usize;
+        self.running[i] = false;
+        if self.enabled && self.pending[i] {
+            self.write_ip(interrupt, true);
+        }
+    }
+}
+
+#[cfg(not(test))]
+mod csr {
+    // The CLIC requires the base address of `mtvec` to be 64-byte aligned in CLIC mode.
+    // Non-vectored traps are forwarded to the trap handler of `riscv-rt`.
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    core::arch::global_asm!(
+        ".section .trap, \"ax\"
+        .balign 64
+        .global _riscv_slic_clic_trap
+        _riscv_slic_clic_trap:
+            j _start_trap"
+    );
+
+    extern "C" {
+        fn _riscv_slic_clic_trap();
+    }
+
+    /// Reads the `mintthresh` register.
+    #[inline]
+    pub unsafe fn read_mintthresh() -> u8 {
+        let mintthresh: usize;
+        core::arch::asm!("csrr {}, 0x347", out(reg) mintthresh);
+        mintthresh as u8
+    }
+
+    /// Reads the interrupt level of the running handler from the `mil` field of the `mintstatus` register.
+    #[inline]
+    pub unsafe fn read_mil() -> u8 {
+        let mintstatus: usize;
+        core::arch::asm!("csrr {}, 0x346", out(reg) mintstatus);
+        (mintstatus >> 24) as u8
+    }
+
+    /// Writes the `mintthresh` register.
+    #[inline]
+    pub unsafe fn write_mintthresh(level: u8) {
+        core::arch::asm!("csrw 0x347, {}", in(reg) level as usize);
+    }
+
+    /// Writes the base address of the vector table of the CLIC in the `mtvt` register.
+    #[inline]
+    pub unsafe fn write_mtvt(vectors: usize) {
+        core::arch::asm!("csrw 0x307, {}", in(reg) vectors);
+    }
+
+    /// Sets the CLIC mode in the `mtvec` register.
+    #[inline]
+    pub unsafe fn write_mtvec_clic() {
+        core::arch::asm!("csrw mtvec, {}", in(reg) _riscv_slic_clic_trap as *const () as usize | 0b11);
+    }
+}
+
+#[cfg(test)]
+mod csr {
+    use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+    /// Emulated `mintthresh` register.
+    pub static MINTTHRESH: AtomicU8 = AtomicU8::new(0);
+    /// Emulated `mil` field of the `mintstatus` register.
+    pub static MIL: AtomicU8 = AtomicU8::new(0);
+    /// Emulated `mtvt` register.
+    pub static MTVT: AtomicUsize = AtomicUsize::new(0);
+    /// Emulated mode bits of the `mtvec` register.
+    pub static MTVEC_MODE: AtomicUsize = AtomicUsize::new(0);
+
+    pub unsafe fn read_mintthresh() -> u8 {
+        MINTTHRESH.load(Ordering::SeqCst)
+    }
+
+    pub unsafe fn read_mil() -> u8 {
+        MIL.load(Ordering::SeqCst)
+    }
+
+    pub unsafe fn write_mintthresh(level: u8) {
+        MINTTHRESH.store(level, Ordering::SeqCst);
+    }
+
+    pub unsafe fn write_mtvt(vectors: usize) {
+        MTVT.store(vectors, Ordering::SeqCst);
+    }
+
+    pub unsafe fn write_mtvec_clic() {
+        MTVEC_MODE.store(0b11, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::sync::atomic::Ordering;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Emulated `clicint*` registers of the CLIC lines.
+    static mut CLICINT: [[u8; 4]; 3] = [[0; 4]; 3];
+
+    /// Mutex for serializing the access to the emulated CLIC.
+    static CLIC_LOCK: Mutex<()> = Mutex::new(());
+
+    #[no_mangle]
+    fn __riscv_slic_clic_int(interrupt: u16) -> *mut u8 {
+        // SAFETY: the tests hold the CLIC lock
+        unsafe { core::ptr::addr_of_mut!(CLICINT[interrupt as usize]) as *mut u8 }
+    }
+
+    #[no_mangle]
+    fn __riscv_slic_clic_vectors() -> usize {
+        0x8000_0040
+    }
+
+    /// Takes the emulated CLIC and resets its registers.
+    fn lock_clic() -> MutexGuard<'static, ()> {
+        let guard = CLIC_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        // SAFETY: we hold the CLIC lock
+        unsafe { CLICINT = [[0xAA; 4]; 3] };
+        csr::MINTTHRESH.store(0, Ordering::SeqCst);
+        csr::MIL.store(0, Ordering::SeqCst);
+        csr::MTVT.store(0, Ordering::SeqCst);
+        csr::MTVEC_MODE.store(0, Ordering::SeqCst);
+        guard
+    }
+
+    /// Returns the emulated `clicint*` registers of a CLIC line.
+    fn clicint(interrupt: usize) -> [u8; 4] {
+        // SAFETY: the tests hold the CLIC lock
+        unsafe { CLICINT[interrupt] }
+    }
+
+    #[test]
+    fn test_init() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [0, 3, 15], [false; 3]);
+        clic.disable_interrupt(2);
+        assert_eq!(clic.get_priority(2), 15);
+        assert!(!clic.is_interrupt_enabled(2));
+        // the CLIC is not touched until the controller is enabled
+        assert_eq!(clicint(0), [0xAA; 4]);
+        assert!(!clic.is_pending(1));
+        assert!(!clic.unpend(1));
+
+        assert!(!clic.enable());
+        assert_eq!(csr::MTVT.load(Ordering::SeqCst), 0x8000_0040);
+        assert_eq!(csr::MTVEC_MODE.load(Ordering::SeqCst), 0b11);
+        // ip, ie, attr, ctl (the 4 unused level bits are set to 1)
+        assert_eq!(clicint(0), [0, 1, CLICINTATTR_VALUE, 0x0F]);
+        assert_eq!(clicint(1), [0, 1, CLICINTATTR_VALUE, 0x3F]);
+        assert_eq!(clicint(2), [0, 0, CLICINTATTR_VALUE, 0xFF]);
+        clic.disable();
+        assert!(!clic.is_ready());
+        assert_eq!(clic.pop(), None);
+    }
+
+    #[test]
+    fn test_priorities() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(2, [1, 2, 3], [false; 3]);
+        assert_eq!(clic.max_priority(), 3);
+        clic.enable();
+        assert_eq!(clicint(2)[CLICINTCTL], 0xFF);
+        clic.set_priority(2, 1);
+        assert_eq!(clic.get_priority(2), 1);
+        assert_eq!(clicint(2)[CLICINTCTL], 0x7F);
+        // priorities that do not fit in the level bits are rejected
+        clic.set_priority(2, 4);
+        assert_eq!(clic.get_priority(2), 1);
+        assert_eq!(clicint(2)[CLICINTCTL], 0x7F);
+
+        let clic = CLIC::new(8, [0, 128, 255], [false; 3]);
+        assert_eq!(clic.max_priority(), 255);
+        assert_eq!(clic.level(128), 128);
+    }
+
+    #[test]
+    #[should_panic(expected = "priority out of range")]
+    fn test_static_priority_out_of_range() {
+        CLIC::new(3, [8], [false]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid CLIC level bits")]
+    fn test_invalid_level_bits() {
+        CLIC::new(9, [0], [false]);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [1, 2, 3], [false; 3]);
+        unsafe { clic.set_threshold(2) };
+        assert_eq!(csr::MINTTHRESH.load(Ordering::SeqCst), 0x2F);
+        assert_eq!(clic.get_threshold(), 2);
+        assert_eq!(clic.raise_threshold(3), Ok(2));
+        assert_eq!(
+            clic.raise_threshold(1),
+            Err(Error::ThresholdNotRaised { current: 3 })
+        );
+        // thresholds above the highest priority mask all the software interrupts
+        unsafe { clic.set_threshold(16) };
+        assert_eq!(csr::MINTTHRESH.load(Ordering::SeqCst), 0xFF);
+        assert_eq!(clic.get_threshold(), 15);
+    }
+
+    #[test]
+    fn test_running_level() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [1, 2, 3], [false; 3]);
+        // the handler of a software interrupt with priority 2 is running
+        csr::MIL.store(clic.level(2), Ordering::SeqCst);
+        assert_eq!(clic.get_threshold(), 2);
+        assert_eq!(
+            clic.raise_threshold(2),
+            Err(Error::ThresholdNotRaised { current: 2 })
+        );
+        // the previous value of mintthresh is returned, so it is restored afterwards
+        assert_eq!(clic.raise_threshold(3), Ok(0));
+        assert_eq!(clic.get_threshold(), 3);
+        unsafe { clic.set_threshold(0) };
+        assert_eq!(clic.get_threshold(), 2);
+        csr::MIL.store(0, Ordering::SeqCst);
+        assert_eq!(clic.get_threshold(), 0);
+    }
+
+    #[test]
+    fn test_pend() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [0, 3, 15], [false; 3]);
+        assert_eq!(clic.check_interrupt(2), Ok(()));
+        assert_eq!(clic.check_interrupt(3), Err(Error::InvalidInterrupt(3)));
+        // pend requests are latched until the controller is enabled
+        clic.pend(1);
+        assert!(clic.is_pending(1));
+        assert_eq!(clicint(1), [0xAA; 4]);
+        assert_eq!(csr::MTVEC_MODE.load(Ordering::SeqCst), 0);
+        clic.enable();
+        assert_eq!(clicint(1), [1, 1, CLICINTATTR_VALUE, 0x3F]);
+        // interrupt sources with priority 0 cannot be pended
+        clic.pend(0);
+        assert!(!clic.is_pending(0));
+        // disabled interrupt sources latch pend requests
+        clic.disable_interrupt(2);
+        clic.pend(2);
+        assert_eq!(clicint(2)[..2], [1, 0]);
+        assert!(!clic.enable_interrupt(2));
+        assert_eq!(clicint(2)[..2], [1, 1]);
+        assert!(clic.unpend(2));
+        assert_eq!(clicint(2)[CLICINTIP], 0);
+        assert!(!clic.unpend(2));
+        // setting the priority of a pending interrupt source to 0 cancels it
+        clic.set_priority(1, 0);
+        assert!(!clic.is_pending(1));
+        assert_eq!(clicint(1)[CLICINTIP], 0);
+
+        clic.set_priority(1, 3);
+        clic.pend(1);
+        clic.start(1);
+        assert!(!clic.is_pending(1));
+        assert!(clic.is_running(1));
+        clic.complete(1);
+        assert!(!clic.is_running(1));
+        assert_eq!(clicint(1)[CLICINTIP], 0);
+    }
+
+    #[test]
+    fn test_disable() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [1, 2, 3], [false; 3]);
+        clic.enable();
+        clic.pend(0);
+        assert_eq!(clicint(0)[CLICINTIP], 1);
+        // disabling the controller clears the CLIC lines, but the pend requests are latched
+        clic.disable();
+        assert_eq!(clicint(0)[CLICINTIP], 0);
+        assert!(clic.is_pending(0));
+        clic.pend(1);
+        assert_eq!(clicint(1)[CLICINTIP], 0);
+        clic.enable();
+        assert_eq!(clicint(0)[CLICINTIP], 1);
+        assert_eq!(clicint(1)[CLICINTIP], 1);
+        assert_eq!(clicint(2)[CLICINTIP], 0);
+    }
+
+    #[test]
+    fn test_counting() {
+        let _clic = lock_clic();
+        let mut clic = CLIC::new(4, [1, 2, 3], [true, false, false]);
+        clic.enable();
+        for _ in 0..3 {
+            clic.pend(0);
+            clic.pend(1);
+        }
+        assert_eq!(clic.get_count(0), 3);
+        assert_eq!(clic.get_count(1), 1);
+        // the handler of a counting interrupt source is triggered again when it completes
+        for count in (0..3).rev() {
+            clic.start(0);
+            assert_eq!(clicint(0)[CLICINTIP], 0);
+            assert_eq!(clic.get_count(0), count);
+            clic.complete(0);
+            assert_eq!(clicint(0)[CLICINTIP], (count > 0) as u8);
+        }
+        assert!(!clic.is_pending(0));
+        clic.start(1);
+        clic.complete(1);
+        assert_eq!(clic.get_count(1), 0);
+        assert_eq!(clicint(1)[CLICINTIP], 0);
+        // unpending a counting interrupt source discards all its pend requests
+        clic.pend(0);
+        clic.pend(0);
+        assert!(clic.unpend(0));
+        assert_eq!(clic.get_count(0), 0);
+        assert_eq!(clicint(0)[CLICINTIP], 0);
+    }
+}
@@ -0,0 +1,48 @@
+//! Errors of the SLIC API.
+
+use core::fmt;
+
+/// Errors reported by the SLIC.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The threshold was not raised, as the current threshold is already higher or equal.
+    ThresholdNotRaised {
+        /// The current threshold of the SLIC.
+        current: u8,
+    },
+    /// The interrupt number does not correspond to any software interrupt source.
+    InvalidInterrupt(u16),
+    /// The priority level is higher than the maximum priority level of the application.
+    PriorityOutOfRange,
+    /// The priority level is higher than the ceiling of a shared resource used by the software interrupt.
+    PriorityAboveCeiling {
+        /// The lowest ceiling of the shared resources used by the software interrupt.
+        ceiling: u8,
+    },
+    /// The software interrupt cannot be pended, as its priority is 0.
+    ZeroPriority(u16),
+    /// The executor has no free slots for spawning a new task.
+    ExecutorFull,
+    /// The queue has no room for a new entry.
+    QueueFull,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ThresholdNotRaised { current } => {
+                write!(f, "threshold not raised (current threshold: {current})")
+            }
+            Self::InvalidInterrupt(interrupt) => write!(f, "invalid interrupt number {interrupt}"),
+            Self::PriorityOutOfRange => write!(f, "priority out of range"),
+            Self::PriorityAboveCeiling { ceiling } => {
+                write!(f, "priority above resource ceiling {ceiling}")
+            }
+            Self::ZeroPriority(interrupt) => {
+                write!(f, "interrupt {interrupt} has priority 0")
+            }
+            Self::ExecutorFull => write!(f, "executor full"),
+            Self::QueueFull => write!(f, "queue full"),
+        }
+    }
+}
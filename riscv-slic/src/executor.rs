@@ -0,0 +1,179 @@
+//! Async executors driven by SLIC software interrupts.
+//!
+//! Each [`Executor`] is bound to a software interrupt source, and it runs its tasks with the
+//! priority of that source. Waking a task pends the software interrupt of its executor, and
+//! the handler of the software interrupt polls the ready tasks of the executor. Thus, you can
+//! use one executor per priority level to get preemptive multi-priority async tasks.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static EXECUTOR: Executor<SoftwareInterrupt, 4> = Executor::new(SoftwareInterrupt::Async);
+//!
+//! #[riscv_slic::handler(Async)]
+//! fn async_handler() {
+//!     // SAFETY: we are in the handler of the software interrupt bound to the executor
+//!     unsafe { EXECUTOR.poll() };
+//! }
+//! ```
+
+use core::cell::{Cell, UnsafeCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, RawWaker, RawWakerVTable, Waker};
+use critical_section::Mutex;
+
+/// Future of a task spawned in an executor.
+pub type TaskFuture = Pin<&'static mut (dyn Future<Output = ()> + Send)>;
+
+/// State of a task slot of an executor.
+#[derive(Clone, Copy)]
+struct TaskState<I> {
+    /// Software interrupt of the executor (set when a task is spawned)
+    interrupt: Option<I>,
+    /// The slot holds a task that has not finished yet
+    spawned: bool,
+    /// The task must be polled
+    ready: bool,
+}
+
+/// Task slot of an executor.
+struct Task<I> {
+    state: Mutex<Cell<TaskState<I>>>,
+    future: UnsafeCell<Option<TaskFuture>>,
+}
+
+impl<I: crate::InterruptNumber> Task<I> {
+    /// Empty task slot. It is only used for initializing the task slots of executors.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Self = Self {
+        state: Mutex::new(Cell::new(TaskState {
+            interrupt: None,
+            spawned: false,
+            ready: false,
+        })),
+        future: UnsafeCell::new(None),
+    };
+
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake, Self::drop);
+
+    /// Creates a waker of the task.
+    fn waker(&'static self) -> Waker {
+        let raw = RawWaker::new(self as *const Self as *const (), &Self::VTABLE);
+        // SAFETY: the vtable functions respect the `RawWaker` contract
+        unsafe { Waker::from_raw(raw) }
+    }
+
+    unsafe fn clone(ptr: *const ()) -> RawWaker {
+        RawWaker::new(ptr, &Self::VTABLE)
+    }
+
+    /// Marks the task as ready and pends the software interrupt of its executor.
+    unsafe fn wake(ptr: *const ()) {
+        // SAFETY: wakers always point to a task of a static executor
+        let task = &*(ptr as *const Self);
+        let interrupt = critical_section::with(|cs| {
+            let state = task.state.borrow(cs);
+            let mut s = state.get();
+            s.ready = true;
+            state.set(s);
+            s.interrupt
+        });
+        if let Some(interrupt) = interrupt {
+            crate::pend(interrupt);
+        }
+    }
+
+    unsafe fn drop(_ptr: *const ()) {}
+}
+
+/// Executor of async tasks bound to a software interrupt source.
+///
+/// The executor can hold up to `N` tasks at the same time.
+pub struct Executor<I, const N: usize> {
+    interrupt: I,
+    tasks: [Task<I>; N],
+}
+
+// SAFETY: task states are protected by critical sections, and futures
+// are only accessed by the handler of the software interrupt of the executor.
+unsafe impl<I: Sync, const N: usize> Sync for Executor<I, N> {}
+
+impl<I: crate::InterruptNumber, const N: usize> Executor<I, N> {
+    /// Creates a new executor bound to a software interrupt source.
+    #[inline]
+    pub const fn new(interrupt: I) -> Self {
+        Self {
+            interrupt,
+            tasks: [Task::NEW; N],
+        }
+    }
+
+    /// Returns the software interrupt source bound to the executor.
+    #[inline]
+    pub fn interrupt(&self) -> I {
+        self.interrupt
+    }
+
+    /// Spawns a new task in the executor and pends its software interrupt.
+    ///
+    /// If the executor is full, it returns [`Error::ExecutorFull`](crate::Error::ExecutorFull).
+    pub fn spawn(&'static self, future: TaskFuture) -> Result<(), crate::Error> {
+        critical_section::with(|cs| {
+            let task = self
+                .tasks
+                .iter()
+                .find(|task| !task.state.borrow(cs).get().spawned);
+            let Some(task) = task else {
+                return Err(crate::Error::ExecutorFull);
+            };
+            // SAFETY: the future of a task that is not spawned is never accessed
+            unsafe { *task.future.get() = Some(future) };
+            task.state.borrow(cs).set(TaskState {
+                interrupt: Some(self.interrupt),
+                spawned: true,
+                ready: true,
+            });
+            Ok(())
+        })?;
+        crate::pend(self.interrupt);
+        Ok(())
+    }
+
+    /// Polls all the ready tasks of the executor once.
+    ///
+    /// # Safety
+    ///
+    /// This function must only be called from the handler of the software interrupt bound to the executor.
+    pub unsafe fn poll(&'static self) {
+        for task in self.tasks.iter() {
+            let ready = critical_section::with(|cs| {
+                let state = task.state.borrow(cs);
+                let mut s = state.get();
+                let ready = s.spawned && s.ready;
+                s.ready = false;
+                state.set(s);
+                ready
+            });
+            if !ready {
+                continue;
+            }
+            let waker = task.waker();
+            let mut cx = Context::from_waker(&waker);
+            // SAFETY: only the handler of the executor accesses the future of a spawned task
+            let future = &mut *task.future.get();
+            if let Some(f) = future {
+                if f.as_mut().poll(&mut cx).is_ready() {
+                    *future = None;
+                    critical_section::with(|cs| {
+                        let state = task.state.borrow(cs);
+                        let mut s = state.get();
+                        s.spawned = false;
+                        state.set(s);
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers for accessing the registers of an IMSIC interrupt file via `*iselect` and `*ireg`.
+//!
+//! The `imsic-backend` feature triggers software interrupts with a dedicated interrupt identity.
+//! The pending and enable bits of each identity live in the `eip*` and `eie*` registers.
+//! Each register holds 32 identities. In RV64, only the even registers exist, and each one
+//! holds 64 identities (i.e., the odd registers are merged with the previous even register).
+//! Pending identities are claimed via the `*topei` register, which reports the pending and
+//! enabled identity with the highest priority.
+
+/// Number of the `eidelivery` register of an interrupt file.
+pub const EIDELIVERY: usize = 0x70;
+/// Number of the `eithreshold` register of an interrupt file.
+pub const EITHRESHOLD: usize = 0x72;
+/// Number of the first interrupt pending register (`eip0`) of an interrupt file.
+pub const EIP0: usize = 0x80;
+/// Number of the first interrupt enable register (`eie0`) of an interrupt file.
+pub const EIE0: usize = 0xC0;
+
+/// Returns the number of the register that holds a given interrupt identity for a given XLEN.
+/// `first` must be the number of the first register of the array (i.e., [`EIP0`] or [`EIE0`]).
+#[inline]
+pub const fn index(first: usize, identity: u16, xlen: u32) -> usize {
+    let identity = identity as usize;
+    match xlen {
+        32 => first + identity / 32,
+        // only even registers are used in RV64
+        _ => first + 2 * (identity / 64),
+    }
+}
+
+/// Returns the position of the bit of a given interrupt identity in its register for a given XLEN.
+#[inline]
+pub const fn bit(identity: u16, xlen: u32) -> u32 {
+    identity as u32 % xlen
+}
+
+/// Returns the register number and the bit mask of the pending bit of a given interrupt identity.
+#[inline]
+pub const fn eip(identity: u16) -> (usize, usize) {
+    let xlen = usize::BITS;
+    (index(EIP0, identity, xlen), 1 << bit(identity, xlen))
+}
+
+/// Returns the register number and the bit mask of the enable bit of a given interrupt identity.
+#[inline]
+pub const fn eie(identity: u16) -> (usize, usize) {
+    let xlen = usize::BITS;
+    (index(EIE0, identity, xlen), 1 << bit(identity, xlen))
+}
+
+/// Returns the interrupt identity reported by a value read from the `*topei` register.
+/// Identity 0 means that there is no pending interrupt.
+#[inline]
+pub const fn topei_identity(topei: usize) -> u16 {
+    ((topei >> 16) & 0x7FF) as u16
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_index_rv32() {
+        assert_eq!(index(EIE0, 1, 32), 0xC0);
+        assert_eq!(index(EIE0, 31, 32), 0xC0);
+        assert_eq!(index(EIE0, 32, 32), 0xC1);
+        assert_eq!(index(EIE0, 63, 32), 0xC1);
+        assert_eq!(index(EIE0, 64, 32), 0xC2);
+        assert_eq!(index(EIE0, 2047, 32), 0xFF);
+        assert_eq!(index(EIP0, 33, 32), 0x81);
+        assert_eq!(bit(33, 32), 1);
+        assert_eq!(bit(2047, 32), 31);
+    }
+
+    #[test]
+    fn test_index_rv64() {
+        assert_eq!(index(EIE0, 1, 64), 0xC0);
+        assert_eq!(index(EIE0, 32, 64), 0xC0);
+        assert_eq!(index(EIE0, 63, 64), 0xC0);
+        assert_eq!(index(EIE0, 64, 64), 0xC2);
+        assert_eq!(index(EIE0, 127, 64), 0xC2);
+        assert_eq!(index(EIE0, 128, 64), 0xC4);
+        assert_eq!(index(EIE0, 2047, 64), 0xFE);
+        assert_eq!(index(EIP0, 65, 64), 0x82);
+        assert_eq!(bit(33, 64), 33);
+        assert_eq!(bit(65, 64), 1);
+    }
+
+    #[test]
+    fn test_eip_eie() {
+        let xlen = usize::BITS;
+        assert_eq!(eip(1), (EIP0, 0b10));
+        assert_eq!(eie(1), (EIE0, 0b10));
+        assert_eq!(eip(70), (index(EIP0, 70, xlen), 1 << bit(70, xlen)));
+        assert_eq!(eie(70), (index(EIE0, 70, xlen), 1 << bit(70, xlen)));
+    }
+
+    #[test]
+    fn test_topei_identity() {
+        assert_eq!(topei_identity(0), 0);
+        // the identity is repeated in the priority field
+        assert_eq!(topei_identity((1 << 16) | 1), 1);
+        assert_eq!(topei_identity((2047 << 16) | 2047), 2047);
+    }
+}
@@ -1,14 +1,38 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 pub use critical_section;
 pub use riscv;
+#[cfg(feature = "plic-backend")]
+pub use riscv_peripheral;
 pub use riscv_slic_macros::*;
 
 mod api;
+#[cfg(any(feature = "clic-backend", test))]
+mod clic;
+mod error;
+pub mod executor;
+#[cfg(any(feature = "imsic-backend", test))]
+pub mod imsic;
+mod message;
+mod mutex;
+#[cfg(any(feature = "plic-backend", test))]
+pub mod plic;
+#[cfg(any(feature = "sbi-backend", feature = "sim-sbi-backend"))]
+pub mod sbi;
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(not(feature = "clic-backend"))]
 mod slic;
+pub mod timer;
 
 pub use api::*;
-pub use slic::{new_slic, MutexSLIC};
+#[cfg(feature = "clic-backend")]
+pub use clic::{new_slic, MutexSLIC};
+pub use error::Error;
+pub use message::{MessageQueue, PayloadInterrupt};
+pub use mutex::PriorityMutex;
+#[cfg(not(feature = "clic-backend"))]
+pub use slic::{new_slic, Fifo, LowestNumberFirst, MutexSLIC, TieBreak};
 
 /// Trait for enums of software interrupt numbers.
 ///
@@ -37,3 +61,30 @@ pub unsafe trait InterruptNumber: Copy {
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u16) -> Result<Self, u16>;
 }
+
+/// Trait for enums of priority levels.
+///
+/// This trait should only be implemented by the [`riscv_slic_macros::codegen`]
+/// macro for the enum of available priority levels. It is not implemented for `u8`,
+/// so the SLIC API only accepts the priority levels that are valid for the application.
+/// Each variant must convert to a `u8` of its priority level.
+///
+/// # Safety
+///
+/// Do NOT implement this trait. It is left for [`riscv_slic_macros::codegen`].
+/// This trait must only be implemented on enums of priority levels. Each
+/// enum variant must represent a distinct value (no duplicates are permitted),
+/// and must always return the same value (do not change at runtime).
+/// All the priority levels must be less than or equal to `MAX_PRIORITY_NUMBER`.
+/// `MAX_PRIORITY_NUMBER` must coincide with the highest allowed priority level.
+pub unsafe trait PriorityNumber: Copy {
+    /// Highest priority level.
+    const MAX_PRIORITY_NUMBER: u8;
+
+    /// Converts a priority to its corresponding level.
+    fn number(self) -> u8;
+
+    /// Tries to convert a level to a valid priority.
+    /// If the conversion fails, it returns an error with the level back.
+    fn from_number(value: u8) -> Result<Self, u8>;
+}
@@ -0,0 +1,298 @@
+//! Message queues for software interrupts with typed payloads.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Trait for software interrupt sources with a typed payload.
+///
+/// This trait is implemented by the [`riscv_slic_macros::codegen`] macro for the marker
+/// types of the software interrupts declared as `<name>: <payload type>; <queue depth>`.
+///
+/// # Safety
+///
+/// Do NOT implement this trait. It is left for [`riscv_slic_macros::codegen`].
+/// The handler of `interrupt` must be the only consumer of the message queue.
+pub unsafe trait PayloadInterrupt: Copy {
+    /// Software interrupt enum.
+    type Interrupt: crate::InterruptNumber;
+    /// Type of the messages sent to the software interrupt handler.
+    type Payload;
+
+    /// Returns the software interrupt source.
+    fn interrupt(self) -> Self::Interrupt;
+
+    /// Enqueues a message for the software interrupt handler.
+    /// If the queue is full, it returns an error with the message back.
+    fn enqueue(self, payload: Self::Payload) -> Result<(), Self::Payload>;
+}
+
+/// Slot of a message queue.
+struct Slot<T> {
+    /// Twice the lap of the next position that uses the slot, plus one if the slot holds a message.
+    stamp: AtomicUsize,
+    message: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    /// Creates a free slot for the first lap.
+    const fn new() -> Self {
+        Self {
+            stamp: AtomicUsize::new(0),
+            message: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// Bounded FIFO queue of messages with up to `N` messages.
+///
+/// Several producers can enqueue messages concurrently, and one consumer dequeues them.
+/// Each slot has a stamp that tells producers and the consumer whether it is free or it
+/// holds a message, so producers only compete for claiming the next position of the queue.
+/// On targets with compare-and-swap instructions, claims are lock-free. Otherwise,
+/// claims are serialized by a short critical section.
+pub struct MessageQueue<T, const N: usize> {
+    slots: [Slot<T>; N],
+    /// Position of the next message to dequeue
+    head: AtomicUsize,
+    /// Position of the next message to enqueue
+    tail: AtomicUsize,
+}
+
+// SAFETY: producers claim distinct slots, and there is only one consumer.
+unsafe impl<T: Send, const N: usize> Sync for MessageQueue<T, N> {}
+
+impl<T, const N: usize> MessageQueue<T, N> {
+    /// Number of laps before positions wrap around.
+    const LAPS: usize = usize::MAX / 2 / N;
+
+    /// Creates a new, empty message queue.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { Slot::new() }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of messages in the queue.
+    /// Messages that are still being enqueued are also counted.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        match tail >= head {
+            true => tail - head,
+            false => tail + Self::LAPS * N - head,
+        }
+    }
+
+    /// Returns `true` if the queue is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the slot of a given position and the stamp of the slot when it is free for that position.
+    #[inline]
+    fn slot(&self, position: usize) -> (&Slot<T>, usize) {
+        (&self.slots[position % N], 2 * (position / N))
+    }
+
+    /// Returns the position that follows a given position.
+    #[inline]
+    fn next(position: usize) -> usize {
+        match position + 1 == Self::LAPS * N {
+            true => 0,
+            false => position + 1,
+        }
+    }
+
+    /// Claims the tail of the queue for enqueueing a message.
+    /// If another producer claimed it before, it returns an error with the current tail.
+    #[cfg(target_has_atomic = "ptr")]
+    #[inline]
+    fn claim(&self, tail: usize) -> Result<(), usize> {
+        self.tail
+            .compare_exchange_weak(tail, Self::next(tail), Ordering::Relaxed, Ordering::Relaxed)
+            .map(|_| ())
+    }
+
+    /// Claims the tail of the queue for enqueueing a message.
+    /// If another producer claimed it before, it returns an error with the current tail.
+    #[cfg(not(target_has_atomic = "ptr"))]
+    #[inline]
+    fn claim(&self, tail: usize) -> Result<(), usize> {
+        critical_section::with(|_| {
+            let current = self.tail.load(Ordering::Relaxed);
+            if current != tail {
+                return Err(current);
+            }
+            self.tail.store(Self::next(tail), Ordering::Relaxed);
+            Ok(())
+        })
+    }
+
+    /// Enqueues a message. If the queue is full, it returns an error with the message back.
+    pub fn enqueue(&self, message: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let (slot, stamp) = self.slot(tail);
+            if slot.stamp.load(Ordering::Acquire) == stamp {
+                match self.claim(tail) {
+                    Ok(()) => {
+                        // SAFETY: the slot is free, and no other producer can claim it in this lap
+                        unsafe { (*slot.message.get()).write(message) };
+                        slot.stamp.store(stamp + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => tail = current,
+                }
+            } else {
+                // if the tail did not move, the slot still holds a message of the previous lap
+                let current = self.tail.load(Ordering::Relaxed);
+                if current == tail {
+                    return Err(message);
+                }
+                tail = current;
+            }
+        }
+    }
+
+    /// Dequeues the oldest message, if any.
+    ///
+    /// # Safety
+    ///
+    /// Only one consumer may dequeue messages from the queue at the same time.
+    pub unsafe fn dequeue(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let (slot, stamp) = self.slot(head);
+        if slot.stamp.load(Ordering::Acquire) != stamp + 1 {
+            return None;
+        }
+        // SAFETY: the slot holds a message that is no longer accessed by producers
+        let message = (*slot.message.get()).assume_init_read();
+        // the slot is free for the next lap
+        let lap = head / N + 1;
+        let lap = if lap == Self::LAPS { 0 } else { lap };
+        slot.stamp.store(2 * lap, Ordering::Release);
+        self.head.store(Self::next(head), Ordering::Release);
+        Some(message)
+    }
+}
+
+impl<T, const N: usize> Default for MessageQueue<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for MessageQueue<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: we have exclusive access to the queue
+        while unsafe { self.dequeue() }.is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo() {
+        let queue = MessageQueue::<u32, 3>::new();
+        assert!(queue.is_empty());
+        for round in 0..5 {
+            for i in 0..3 {
+                assert_eq!(queue.enqueue(round * 10 + i), Ok(()));
+            }
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.enqueue(100), Err(100));
+            for i in 0..3 {
+                assert_eq!(unsafe { queue.dequeue() }, Some(round * 10 + i));
+            }
+            assert_eq!(unsafe { queue.dequeue() }, None);
+        }
+    }
+
+    #[test]
+    fn test_single_slot() {
+        let queue = MessageQueue::<u32, 1>::new();
+        for i in 0..3 {
+            assert_eq!(queue.enqueue(i), Ok(()));
+            assert_eq!(queue.enqueue(100), Err(100));
+            assert_eq!(unsafe { queue.dequeue() }, Some(i));
+            assert_eq!(unsafe { queue.dequeue() }, None);
+        }
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        type Queue = MessageQueue<u32, 3>;
+
+        // move the queue to the last position before wrapping around
+        let mut queue = Queue::new();
+        let last = Queue::LAPS * 3 - 1;
+        *queue.head.get_mut() = last;
+        *queue.tail.get_mut() = last;
+        *queue.slots[last % 3].stamp.get_mut() = 2 * (Queue::LAPS - 1);
+        for i in 0..3 {
+            assert_eq!(queue.enqueue(i), Ok(()));
+            assert_eq!(queue.len(), i as usize + 1);
+        }
+        assert_eq!(queue.enqueue(100), Err(100));
+        for i in 0..3 {
+            assert_eq!(unsafe { queue.dequeue() }, Some(i));
+        }
+        assert!(queue.is_empty());
+        assert_eq!(queue.enqueue(3), Ok(()));
+        assert_eq!(unsafe { queue.dequeue() }, Some(3));
+    }
+
+    #[test]
+    fn test_producers() {
+        const PRODUCERS: usize = 4;
+        const MESSAGES: usize = 1000;
+
+        let queue = MessageQueue::<(usize, usize), 8>::new();
+        std::thread::scope(|s| {
+            for producer in 0..PRODUCERS {
+                let queue = &queue;
+                s.spawn(move || {
+                    for i in 0..MESSAGES {
+                        while queue.enqueue((producer, i)).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+            // messages of each producer are dequeued in order
+            let mut next = [0; PRODUCERS];
+            while next.iter().any(|&i| i < MESSAGES) {
+                match unsafe { queue.dequeue() } {
+                    Some((producer, i)) => {
+                        assert_eq!(i, next[producer]);
+                        next[producer] += 1;
+                    }
+                    None => std::thread::yield_now(),
+                }
+            }
+        });
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        use std::rc::Rc;
+
+        let message = Rc::new(());
+        let queue = MessageQueue::<Rc<()>, 2>::new();
+        queue.enqueue(message.clone()).unwrap();
+        queue.enqueue(message.clone()).unwrap();
+        assert_eq!(Rc::strong_count(&message), 3);
+        drop(queue);
+        assert_eq!(Rc::strong_count(&message), 1);
+    }
+}
@@ -0,0 +1,91 @@
+//! Mutex based on the priority threshold of the SLIC.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Mutex that grants exclusive access to its data by raising the SLIC threshold to `CEILING`.
+///
+/// Unlike critical sections, software interrupts with a priority higher than `CEILING`
+/// can still preempt the owner of the lock. Thus, `CEILING` must be greater than or equal
+/// to the priority of every software interrupt that locks the mutex.
+///
+/// The mutex also keeps track of its lock state. If a task locks the mutex twice, or if a task
+/// with a priority higher than `CEILING` tries to lock the mutex while it is locked, it panics.
+///
+/// # Note
+///
+/// The SLIC only masks software interrupts of the current hart. Thus, the mutex must only
+/// be shared among tasks that run on the same hart.
+pub struct PriorityMutex<T, const CEILING: u8> {
+    data: UnsafeCell<T>,
+    locked: AtomicBool,
+}
+
+// SAFETY: the mutex only grants access to its data to one task at a time.
+unsafe impl<T: Send, const CEILING: u8> Sync for PriorityMutex<T, CEILING> {}
+
+impl<T, const CEILING: u8> PriorityMutex<T, CEILING> {
+    /// Creates a new mutex that owns `data`.
+    #[inline]
+    pub const fn new(data: T) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// As this call borrows the mutex mutably, no actual locking needs to take place.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consumes the mutex, returning the underlying data.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+
+    /// Runs a function with exclusive access to the data of the mutex.
+    /// This function returns the return value of the target function.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked or if `CEILING`
+    /// is higher than the maximum priority level of the application.
+    #[inline]
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        assert!(
+            crate::api::is_valid_priority(CEILING),
+            "priority ceiling out of range"
+        );
+        let _guard = crate::api::raise_level(CEILING);
+        self.acquire();
+        // SAFETY: the pointer to the data is valid, and the lock state prevents aliasing
+        let r = f(unsafe { &mut *self.data.get() });
+        // the mutex must be released before restoring the threshold
+        self.locked.store(false, Ordering::SeqCst);
+        r
+    }
+
+    /// Marks the mutex as locked.
+    #[inline]
+    fn acquire(&self) {
+        // In the simulator, several threads may share the mutex.
+        #[cfg(feature = "sim")]
+        let locked = self.locked.swap(true, Ordering::SeqCst);
+        // Otherwise, preempting tasks always release the mutex before returning,
+        // so we do not need an atomic swap here.
+        #[cfg(not(feature = "sim"))]
+        let locked = {
+            let locked = self.locked.load(Ordering::SeqCst);
+            self.locked.store(true, Ordering::SeqCst);
+            locked
+        };
+        if locked {
+            panic!("mutex already locked");
+        }
+    }
+}
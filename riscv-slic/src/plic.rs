@@ -0,0 +1,56 @@
+//! Helpers for triggering software interrupts with a spare source of the PLIC.
+//!
+//! The `plic-backend` feature triggers software interrupts by setting the pending bit of a PLIC source.
+//! Note that the PLIC specification defines the pending array as read-only, and most PLICs ignore
+//! writes to it. Thus, this backend only works on PLICs with software-settable pending bits.
+
+/// Offset of the pending array from the base address of the PLIC.
+pub const PENDINGS_OFFSET: usize = 0x1000;
+
+/// Sets the pending bit of a given PLIC source.
+///
+/// On targets with atomic instructions, the bit is set with a single `amoor.w` instruction.
+/// Otherwise, the read-modify-write sequence runs within a critical section, so it is atomic
+/// with respect to other software on this HART. However, the PLIC gateways may still modify the
+/// pending bits of other sources of the same word in between. Thus, on these targets, the spare
+/// source must not share its 32-bit word of the pending array with active sources.
+///
+/// # Safety
+///
+/// `base` must be the base address of a PLIC with software-settable pending bits.
+#[inline]
+pub unsafe fn set_pending(base: usize, source: usize) {
+    let pending = ((base + PENDINGS_OFFSET) as *mut u32).add(source / u32::BITS as usize);
+    let mask = 1 << (source % u32::BITS as usize);
+    #[cfg(target_has_atomic = "32")]
+    {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        // SAFETY: the pending word is 4-byte aligned and only accessed atomically by the SLIC
+        AtomicU32::from_ptr(pending).fetch_or(mask, Ordering::SeqCst);
+    }
+    #[cfg(not(target_has_atomic = "32"))]
+    critical_section::with(|_| pending.write_volatile(pending.read_volatile() | mask));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_pending() {
+        // the pending array is the only part of the emulated PLIC that we need
+        let mut plic = [0u32; (PENDINGS_OFFSET + 8) / 4];
+        let base = plic.as_mut_ptr() as usize;
+        let pendings = PENDINGS_OFFSET / 4;
+
+        unsafe { set_pending(base, 1) };
+        assert_eq!(plic[pendings], 0b10);
+        unsafe { set_pending(base, 31) };
+        assert_eq!(plic[pendings], 0b10 | 1 << 31);
+        unsafe { set_pending(base, 32) };
+        assert_eq!(plic[pendings], 0b10 | 1 << 31);
+        assert_eq!(plic[pendings + 1], 0b1);
+        unsafe { set_pending(base, 1) };
+        assert_eq!(plic[pendings], 0b10 | 1 << 31);
+    }
+}
@@ -0,0 +1,48 @@
+//! Helpers for sending IPIs via the Supervisor Binary Interface (SBI).
+//!
+//! The `sbi-backend` feature triggers software interrupts with the `sbi_send_ipi` function of the
+//! SBI IPI extension. The environment call itself is performed by the `__riscv_slic_sbi_ecall` hook,
+//! which is generated by the [`riscv_slic_macros::codegen`] macro. By default, this hook executes
+//! the `ecall` instruction, but applications can forward it to their own function (e.g., a stand-in
+//! for the SBI implementation in tests) with the `ecall` argument of the backend.
+//!
+//! The `sim-sbi-backend` feature runs the SBI backend on the host-side simulated hart, so the
+//! generated pend and unpend functions can be tested against a stand-in SBI implementation.
+
+/// Extension ID of the SBI IPI extension ("sPI").
+pub const EID_IPI: usize = 0x735049;
+/// Function ID of the `sbi_send_ipi` function of the SBI IPI extension.
+pub const FID_SEND_IPI: usize = 0;
+
+extern "Rust" {
+    fn __riscv_slic_sbi_ecall(eid: usize, fid: usize, arg0: usize, arg1: usize) -> isize;
+}
+
+/// Sends an IPI to a given HART via the `sbi_send_ipi` function of the SBI IPI extension.
+/// If the SBI implementation fails, it returns the SBI error code.
+///
+/// # Safety
+///
+/// This function is only for `riscv-slic` internal use. Do not call it directly.
+#[inline]
+pub unsafe fn send_ipi(hart_id: usize) -> Result<(), isize> {
+    // hart_mask = 1, hart_mask_base = hart_id (i.e., only the target HART)
+    match __riscv_slic_sbi_ecall(EID_IPI, FID_SEND_IPI, 1, hart_id) {
+        0 => Ok(()),
+        error => Err(error),
+    }
+}
+
+/// Clears the pending IPI of the current HART (i.e., the `SSIP` bit of the `sip` register).
+///
+/// # Safety
+///
+/// This function is only for `riscv-slic` internal use. Do not call it directly.
+#[inline]
+pub unsafe fn clear_ipi() {
+    #[cfg(not(feature = "sim"))]
+    riscv::register::sip::clear_ssoft();
+    // the simulated hart emulates the pending bit of software interrupts
+    #[cfg(feature = "sim")]
+    crate::sim::unpend();
+}
@@ -0,0 +1,161 @@
+//! Host-side simulation of a RISC-V hart for testing SLIC-based applications.
+//!
+//! This module emulates the bits of the hart that the SLIC relies on: the global interrupt
+//! enable flag (`mstatus.MIE`), the software interrupt enable flag (`mie.MSIE`), and the
+//! software interrupt pending bit (`mip.MSIP`). Whenever the pending bit is set and both
+//! enable flags are active, the simulated hart "traps" and runs the SLIC software interrupt
+//! handler, just like a real core would do.
+//!
+//! This module also provides a [`critical_section`] implementation that masks the simulated
+//! interrupts. Thus, pending a software interrupt within a critical section is deferred until
+//! the critical section is released, mirroring the behavior on real hardware.
+//!
+//! # Note
+//!
+//! The simulation emulates a single hart shared by all the threads of the process.
+//! As `cargo test` runs tests in parallel, tests that interact with the SLIC must hold
+//! the guard returned by [`lock_hart`] to avoid interfering with each other.
+
+extern crate std;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+extern "Rust" {
+    fn __riscv_slic_swi_handler();
+}
+
+/// Emulated global interrupt enable flag (`mstatus.MIE`).
+static IE: AtomicBool = AtomicBool::new(false);
+/// Emulated software interrupt enable flag (`mie.MSIE`).
+static SWIE: AtomicBool = AtomicBool::new(false);
+/// Emulated software interrupt pending flag (`mip.MSIP`).
+static SWIP: AtomicBool = AtomicBool::new(false);
+
+/// Mutex for serializing the access to the simulated hart.
+static HART: Mutex<()> = Mutex::new(());
+
+/// Takes exclusive ownership of the simulated hart.
+///
+/// The simulated hart is reset to its initial state: interrupts are disabled and
+/// no software interrupt is pending. Note that the SLIC state is not modified.
+pub fn lock_hart() -> MutexGuard<'static, ()> {
+    let guard = match HART.lock() {
+        Ok(guard) => guard,
+        // a previous test panicked while holding the hart. We just take it over.
+        Err(err) => err.into_inner(),
+    };
+    IE.store(false, Ordering::SeqCst);
+    SWIE.store(false, Ordering::SeqCst);
+    SWIP.store(false, Ordering::SeqCst);
+    guard
+}
+
+/// Returns `true` if the simulated software interrupt is pending.
+#[inline]
+pub fn is_pending() -> bool {
+    SWIP.load(Ordering::SeqCst)
+}
+
+/// Returns `true` if interrupts are enabled in the simulated hart.
+#[inline]
+pub fn is_enabled() -> bool {
+    IE.load(Ordering::SeqCst)
+}
+
+/// Sets the simulated software interrupt pending bit.
+///
+/// If interrupts are enabled, the software interrupt handler runs before this function returns.
+#[inline]
+pub fn pend() {
+    SWIP.store(true, Ordering::SeqCst);
+    dispatch();
+}
+
+/// Clears the simulated software interrupt pending bit.
+#[inline]
+pub fn unpend() {
+    SWIP.store(false, Ordering::SeqCst);
+}
+
+/// Disables interrupts in the simulated hart.
+#[inline]
+pub fn disable() {
+    IE.store(false, Ordering::SeqCst);
+}
+
+/// Enables interrupts in the simulated hart and runs any pending software interrupt.
+///
+/// # Safety
+///
+/// Do not call this function inside a critical section.
+#[inline]
+pub unsafe fn enable() {
+    IE.store(true, Ordering::SeqCst);
+    dispatch();
+}
+
+/// Disables software interrupts in the simulated hart.
+///
+/// # Safety
+///
+/// This function has the same signature as its hardware counterpart.
+/// It is always safe to disable software interrupts.
+#[inline]
+pub unsafe fn disable_swi() {
+    SWIE.store(false, Ordering::SeqCst);
+}
+
+/// Enables software interrupts in the simulated hart and runs any pending software interrupt.
+///
+/// # Safety
+///
+/// This function may break mask-based critical sections.
+#[inline]
+pub unsafe fn enable_swi() {
+    SWIE.store(true, Ordering::SeqCst);
+    dispatch();
+}
+
+/// Executes a closure with interrupts enabled in the simulated hart.
+///
+/// This is the simulated counterpart of [`riscv::interrupt::nested`].
+#[inline]
+pub fn nested<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let prev = IE.swap(true, Ordering::SeqCst);
+    dispatch();
+    let r = f();
+    IE.store(prev, Ordering::SeqCst);
+    r
+}
+
+/// Runs the software interrupt handler while the simulated hart is ready to take it.
+fn dispatch() {
+    while IE.load(Ordering::SeqCst) && SWIE.load(Ordering::SeqCst) && SWIP.load(Ordering::SeqCst) {
+        // trap entry: interrupts are disabled while running the handler
+        IE.store(false, Ordering::SeqCst);
+        // SAFETY: the handler is provided by the code generated by `codegen!`
+        unsafe { __riscv_slic_swi_handler() };
+        // trap exit: interrupts are enabled again
+        IE.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Critical section implementation that masks the interrupts of the simulated hart.
+struct SimCriticalSection;
+critical_section::set_impl!(SimCriticalSection);
+
+unsafe impl critical_section::Impl for SimCriticalSection {
+    unsafe fn acquire() -> bool {
+        IE.swap(false, Ordering::SeqCst)
+    }
+
+    unsafe fn release(was_active: bool) {
+        if was_active {
+            enable();
+        }
+    }
+}
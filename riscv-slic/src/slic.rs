@@ -1,20 +1,93 @@
+use crate::Error;
 use core::cell::RefCell;
 use critical_section::Mutex;
-use heapless::binary_heap::{BinaryHeap, Max};
+
+#[cfg(feature = "bitmap-scheduler")]
+mod bitmap;
+
+#[cfg(not(feature = "bitmap-scheduler"))]
+mod heap;
 
 #[doc(hidden)]
-pub type MutexSLIC<const N: usize> = Mutex<RefCell<SLIC<N>>>;
+pub type MutexSLIC<const N: usize, T = Fifo> = Mutex<RefCell<SLIC<N, T>>>;
 
 #[doc(hidden)]
 #[inline]
-pub const fn new_slic<const N: usize>() -> MutexSLIC<N> {
-    Mutex::new(RefCell::new(SLIC::new()))
+pub const fn new_slic<const N: usize, T: TieBreak>(
+    priorities: [u8; N],
+    counting: [bool; N],
+) -> MutexSLIC<N, T> {
+    Mutex::new(RefCell::new(SLIC::new(priorities, counting)))
 }
 
+/// Ready queue with the pending and enabled interrupt sources of a [`SLIC`].
+pub trait Queue: core::fmt::Debug {
+    /// Empty queue.
+    const EMPTY: Self;
+
+    /// Returns the priority of the next entry of the queue.
+    fn peek(&self) -> Option<u8>;
+
+    /// Pushes an interrupt source to the queue.
+    ///
+    /// # Safety
+    ///
+    /// The same interrupt source must not be queued more than once.
+    unsafe fn push(&mut self, priority: u8, interrupt: u16);
+
+    /// Removes an interrupt source from the queue.
+    ///
+    /// # Safety
+    ///
+    /// The interrupt source must be queued with the given priority.
+    unsafe fn remove(&mut self, priority: u8, interrupt: u16);
+
+    /// Pops the entry with highest priority of the queue.
+    fn pop(&mut self) -> Option<(u8, u16)>;
+}
+
+/// Tie-break policy for pending interrupt sources with the same priority.
+///
+/// The policy of an application is selected with the `tie_break` argument of the
+/// [`riscv_slic_macros::codegen`] macro, so it does not affect other applications.
+pub trait TieBreak {
+    /// Ready queue that serves interrupt sources according to the policy.
+    type Queue<const N: usize>: Queue;
+}
+
+/// Interrupt sources with the same priority are served in the order they were pended (default).
+#[derive(Debug)]
+pub struct Fifo;
+
+/// Interrupt sources with the same priority are served by ascending interrupt number.
+#[derive(Debug)]
+pub struct LowestNumberFirst;
+
 /// Software interrupt controller
+///
+/// # Tie-break policy
+///
+/// Pending interrupts are served in descending priority order. Interrupts with
+/// the same priority are served according to the [`TieBreak`] policy `T`:
+/// in the order they were pended with [`Fifo`] (default), or by ascending
+/// interrupt number with [`LowestNumberFirst`].
+///
+/// # Counting mode
+///
+/// By default, pending an interrupt source that is already pending has no effect.
+/// Interrupt sources in counting mode keep track of how many times they have been
+/// pended instead, and their handler is dispatched once per pend request.
+/// The counter saturates at `u16::MAX` pend requests.
+///
+/// # Ready queue
+///
+/// By default, pending interrupts are kept in a binary heap, so pending and popping
+/// interrupts are O(log N) operations. If the `bitmap-scheduler` feature is enabled,
+/// the controller keeps a bitmap of priority levels instead, and pending and popping
+/// interrupts take constant time at the cost of a bigger memory footprint.
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
-pub struct SLIC<const N: usize> {
+pub struct SLIC<const N: usize, T: TieBreak = Fifo> {
     /// Enable flag. If `false`, the controller will not trigger software interrupts.
     enabled: bool,
     /// Priority threshold. The controller only triggers software
@@ -23,22 +96,37 @@ pub struct SLIC<const N: usize> {
     /// Array with the priorities assigned to each software interrupt source.
     /// Priority 0 is reserved for "interrupt diabled".
     priorities: [u8; N],
+    /// Array to check if a software interrupt source is enabled.
+    /// Disabled sources can be pended, but they are not queued until they are enabled.
+    enables: [bool; N],
+    /// Array to check if a software interrupt source is in counting mode.
+    counting: [bool; N],
     /// Array to check if a software interrupt source is pending.
     pending: [bool; N],
-    /// Priority queue with pending interrupt sources.
-    queue: BinaryHeap<(u8, u16), Max, N>,
+    /// Array with the number of pend requests of each software interrupt source in counting mode.
+    counts: [u16; N],
+    /// Array to check if the handler of a software interrupt source is running.
+    running: [bool; N],
+    /// Priority queue with pending and enabled interrupt sources.
+    queue: T::Queue<N>,
 }
 
-impl<const N: usize> SLIC<N> {
-    /// Creates a new software interrupt controller protected by a mutex.
+impl<const N: usize, T: TieBreak> SLIC<N, T> {
+    /// Creates a new software interrupt controller.
+    /// The `priorities` array contains the initial priority of each interrupt source.
+    /// The `counting` array indicates which interrupt sources are in counting mode.
     #[inline]
-    const fn new() -> Self {
+    const fn new(priorities: [u8; N], counting: [bool; N]) -> Self {
         Self {
             enabled: false,
             threshold: 0,
-            priorities: [0; N],
+            priorities,
+            enables: [true; N],
+            counting,
             pending: [false; N],
-            queue: BinaryHeap::new(),
+            counts: [0; N],
+            running: [false; N],
+            queue: <T::Queue<N> as Queue>::EMPTY,
         }
     }
 
@@ -68,12 +156,62 @@ impl<const N: usize> SLIC<N> {
     ///
     /// The 0 priority level is reserved for "never interrupt".
     ///
-    /// Interrupts are queued according to their priority level when queued.
-    /// Thus, if you change the priority of an interrupt while it is already queued,
-    /// the pending interrupt will execute with the previous priority.
+    /// If the interrupt is already pending, it is re-queued as if it was pended again
+    /// with the new priority. If the new priority is 0, the pending interrupt is dropped.
     #[inline]
     pub fn set_priority(&mut self, interrupt: u16, priority: u8) {
-        self.priorities[interrupt as usize] = priority;
+        let i = interrupt as usize;
+        let prev = self.priorities[i];
+        self.priorities[i] = priority;
+        if self.pending[i] && prev != priority {
+            if self.enables[i] {
+                // SAFETY: pending and enabled interrupts are queued with their current priority
+                unsafe { self.queue.remove(prev, interrupt) };
+            }
+            if priority == 0 {
+                self.pending[i] = false;
+                self.counts[i] = 0;
+            } else if self.enables[i] {
+                // SAFETY: the interrupt was removed from the queue
+                unsafe { self.queue.push(priority, interrupt) };
+            }
+        }
+    }
+
+    /// Checks if a given interrupt source is enabled.
+    #[inline]
+    pub fn is_interrupt_enabled(&self, interrupt: u16) -> bool {
+        self.enables[interrupt as usize]
+    }
+
+    /// Enables an interrupt source. If the interrupt source is pending,
+    /// it is queued as if it was pended right now.
+    /// Returns `true` if the controller is ready to trigger an interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, interrupt: u16) -> bool {
+        let i = interrupt as usize;
+        if !self.enables[i] {
+            self.enables[i] = true;
+            if self.pending[i] {
+                // SAFETY: disabled interrupts are not queued
+                unsafe { self.queue.push(self.priorities[i], interrupt) };
+            }
+        }
+        self.is_ready()
+    }
+
+    /// Disables an interrupt source. If the interrupt source is pending,
+    /// it remains pending until it is enabled again.
+    #[inline]
+    pub fn disable_interrupt(&mut self, interrupt: u16) {
+        let i = interrupt as usize;
+        if self.enables[i] {
+            self.enables[i] = false;
+            if self.pending[i] {
+                // SAFETY: pending and enabled interrupts are queued with their current priority
+                unsafe { self.queue.remove(self.priorities[i], interrupt) };
+            }
+        }
     }
 
     //// Returns current priority threshold.
@@ -96,28 +234,58 @@ impl<const N: usize> SLIC<N> {
 
     /// Sets the priority threshold only to a higher value than the current threshold.
     /// When the threshold is raised, the function returns `Ok(prev_threshold)`.
-    /// Otherwise, the threshold is not changed and `Err(())` is returned.
-    pub fn raise_threshold(&mut self, priority: u8) -> Result<u8, ()> {
+    /// Otherwise, the threshold is not changed and [`Error::ThresholdNotRaised`] is returned.
+    pub fn raise_threshold(&mut self, priority: u8) -> Result<u8, Error> {
         if priority > self.threshold {
             let prev = self.threshold;
             self.threshold = priority;
             Ok(prev)
         } else {
-            Err(())
+            Err(Error::ThresholdNotRaised {
+                current: self.threshold,
+            })
+        }
+    }
+
+    /// Checks that a given interrupt number corresponds to a software interrupt source.
+    #[inline]
+    pub fn check_interrupt(&self, interrupt: u16) -> Result<(), Error> {
+        if (interrupt as usize) < N {
+            Ok(())
+        } else {
+            Err(Error::InvalidInterrupt(interrupt))
         }
     }
 
     /// Checks if a given interrupt is pending.
     #[inline]
-    pub fn is_pending(&mut self, interrupt: u16) -> bool {
+    pub fn is_pending(&self, interrupt: u16) -> bool {
         self.pending[interrupt as usize]
     }
 
+    /// Returns the number of pending requests of a given interrupt.
+    /// For interrupt sources that are not in counting mode, this is at most 1.
+    #[inline]
+    pub fn get_count(&self, interrupt: u16) -> u16 {
+        let i = interrupt as usize;
+        match self.counting[i] {
+            true => self.counts[i],
+            false => self.pending[i] as u16,
+        }
+    }
+
+    /// Checks if the handler of a given interrupt is running.
+    /// Preempted handlers are also considered as running.
+    #[inline]
+    pub fn is_running(&self, interrupt: u16) -> bool {
+        self.running[interrupt as usize]
+    }
+
     /// Returns `true` if the next queued interrupt can be triggered.
     #[inline]
     pub fn is_ready(&self) -> bool {
         self.enabled
-            && match self.queue.peek().map(|&(p, _)| p) {
+            && match self.queue.peek() {
                 Some(p) => p > self.threshold,
                 None => false,
             }
@@ -127,33 +295,677 @@ impl<const N: usize> SLIC<N> {
     ///
     /// # Notes
     ///
-    /// If interrupt priority is 0 or already pending, this request is silently ignored.
+    /// If interrupt priority is 0, this request is silently ignored.
+    /// If the interrupt source is already pending, this request is silently ignored
+    /// unless the interrupt source is in counting mode.
+    /// If the interrupt source is disabled, it is not queued until it is enabled again.
     #[inline]
     pub fn pend(&mut self, interrupt: u16) {
         let i = interrupt as usize;
         if self.priorities[i] == 0 {
             return;
         }
+        if self.counting[i] {
+            self.counts[i] = self.counts[i].saturating_add(1);
+        }
         // set the task to pending and push to the queue if it was not pending beforehand.
         if !self.pending[i] {
             self.pending[i] = true;
-            // SAFETY: we guarantee that the same task can not be pending more than once
-            unsafe { self.queue.push_unchecked((self.priorities[i], interrupt)) };
+            if self.enables[i] {
+                // SAFETY: we guarantee that the same task can not be pending more than once
+                unsafe { self.queue.push(self.priorities[i], interrupt) };
+            }
         }
     }
 
-    /// Pops the pending tasks with highest priority.
+    /// Clears the pending state of an interrupt source.
+    /// For interrupt sources in counting mode, all the pend requests are discarded.
+    /// Returns `true` if the interrupt source was pending.
+    #[inline]
+    pub fn unpend(&mut self, interrupt: u16) -> bool {
+        let i = interrupt as usize;
+        let was_pending = self.pending[i];
+        if was_pending {
+            self.pending[i] = false;
+            self.counts[i] = 0;
+            if self.enables[i] {
+                // SAFETY: pending and enabled interrupts are queued with their current priority
+                unsafe { self.queue.remove(self.priorities[i], interrupt) };
+            }
+        }
+        was_pending
+    }
+
+    /// Pops the pending tasks with highest priority and marks it as running.
+    /// Once its handler is done, you must call the [`SLIC::complete`] method.
+    ///
+    /// Interrupt sources in counting mode with more pend requests remain pending
+    /// and are queued again, as if they were pended right now.
     #[inline]
     pub fn pop(&mut self) -> Option<(u8, u16)> {
         while self.is_ready() {
             // SAFETY: we guarantee that the queue is not empty
-            let (priority, interrupt) = unsafe { self.queue.pop_unchecked() };
+            let (priority, interrupt) = unsafe { self.queue.pop().unwrap_unchecked() };
             let i = interrupt as usize;
             if self.pending[i] {
-                self.pending[i] = false;
+                if self.counts[i] > 1 {
+                    self.counts[i] -= 1;
+                    // SAFETY: the interrupt was popped from the queue
+                    unsafe { self.queue.push(priority, interrupt) };
+                } else {
+                    self.counts[i] = 0;
+                    self.pending[i] = false;
+                }
+                self.running[i] = true;
                 return Some((priority, interrupt));
             }
         }
         None
     }
+
+    /// Marks the handler of a given interrupt as done.
+    #[inline]
+    pub fn complete(&mut self, interrupt: u16) {
+        self.running[interrupt as usize] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cmp::Reverse;
+
+    /// Creates a SLIC with the given priorities, enabled and with threshold 0.
+    fn slic<const N: usize>(priorities: [u8; N]) -> SLIC<N> {
+        policy_slic(priorities)
+    }
+
+    /// Creates a SLIC with the given priorities and tie-break policy, enabled and with threshold 0.
+    fn policy_slic<const N: usize, T: TieBreak>(priorities: [u8; N]) -> SLIC<N, T> {
+        let mut slic = SLIC::new(priorities, [false; N]);
+        slic.enable();
+        slic
+    }
+
+    #[test]
+    fn test_new() {
+        let mut slic = SLIC::<4>::new([0; 4], [false; 4]);
+        assert!(!slic.enabled);
+        assert_eq!(slic.get_threshold(), 0);
+        for i in 0..4 {
+            assert_eq!(slic.get_priority(i), 0);
+            assert!(!slic.is_pending(i));
+        }
+        assert!(!slic.is_ready());
+        assert!(!slic.enable());
+        assert_eq!(slic.pop(), None);
+
+        // static priorities
+        let slic = SLIC::<2>::new([3, 0], [false; 2]);
+        assert_eq!(slic.get_priority(0), 3);
+        assert_eq!(slic.get_priority(1), 0);
+    }
+
+    #[test]
+    fn test_pend_priority_zero() {
+        let mut slic = slic([0, 1]);
+        slic.pend(0);
+        assert!(!slic.is_pending(0));
+        assert!(!slic.is_ready());
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_pend_duplicate() {
+        let mut slic = slic([1, 2]);
+        slic.pend(0);
+        slic.pend(0);
+        assert!(slic.is_pending(0));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert!(!slic.is_pending(0));
+        // the interrupt was queued only once
+        assert!(slic.queue.peek().is_none());
+        assert_eq!(slic.pop(), None);
+        // once popped, the interrupt can be pended again
+        slic.pend(0);
+        assert_eq!(slic.pop(), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_pop_priority_order() {
+        let mut slic = slic([2, 1, 3, 2]);
+        for i in 0..4 {
+            slic.pend(i);
+        }
+        assert_eq!(slic.pop(), Some((3, 2)));
+        assert_eq!(slic.pop(), Some((2, 0)));
+        assert_eq!(slic.pop(), Some((2, 3)));
+        assert_eq!(slic.pop(), Some((1, 1)));
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_pop_fifo_order() {
+        let mut slic = slic([1, 1, 1, 2]);
+        slic.pend(2);
+        slic.pend(0);
+        slic.pend(3);
+        slic.pend(1);
+        assert_eq!(slic.pop(), Some((2, 3)));
+        assert_eq!(slic.pop(), Some((1, 2)));
+        slic.pend(2);
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert_eq!(slic.pop(), Some((1, 1)));
+        assert_eq!(slic.pop(), Some((1, 2)));
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_pop_lowest_number_first() {
+        let mut slic = policy_slic::<4, LowestNumberFirst>([1, 1, 1, 2]);
+        slic.pend(2);
+        slic.pend(0);
+        slic.pend(3);
+        slic.pend(1);
+        assert_eq!(slic.pop(), Some((2, 3)));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        slic.pend(0);
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert_eq!(slic.pop(), Some((1, 1)));
+        assert_eq!(slic.pop(), Some((1, 2)));
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_threshold_boundaries() {
+        let mut slic = slic([1, 2, 255]);
+        slic.pend(1);
+        unsafe { slic.set_threshold(1) };
+        assert!(slic.is_ready());
+        unsafe { slic.set_threshold(2) };
+        assert!(!slic.is_ready());
+        assert_eq!(slic.pop(), None);
+        assert!(slic.is_pending(1));
+
+        slic.pend(2);
+        unsafe { slic.set_threshold(254) };
+        assert_eq!(slic.pop(), Some((255, 2)));
+        unsafe { slic.set_threshold(255) };
+        slic.pend(2);
+        assert!(!slic.is_ready());
+        assert_eq!(slic.pop(), None);
+
+        unsafe { slic.set_threshold(0) };
+        assert_eq!(slic.pop(), Some((255, 2)));
+        assert_eq!(slic.pop(), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_check_interrupt() {
+        let slic = slic([1, 2]);
+        assert_eq!(slic.check_interrupt(0), Ok(()));
+        assert_eq!(slic.check_interrupt(1), Ok(()));
+        assert_eq!(slic.check_interrupt(2), Err(Error::InvalidInterrupt(2)));
+        assert_eq!(
+            slic.check_interrupt(u16::MAX),
+            Err(Error::InvalidInterrupt(u16::MAX))
+        );
+    }
+
+    #[test]
+    fn test_raise_threshold() {
+        let mut slic = slic([1]);
+        assert_eq!(
+            slic.raise_threshold(0),
+            Err(Error::ThresholdNotRaised { current: 0 })
+        );
+        assert_eq!(slic.raise_threshold(2), Ok(0));
+        assert_eq!(slic.get_threshold(), 2);
+        assert_eq!(
+            slic.raise_threshold(2),
+            Err(Error::ThresholdNotRaised { current: 2 })
+        );
+        assert_eq!(
+            slic.raise_threshold(1),
+            Err(Error::ThresholdNotRaised { current: 2 })
+        );
+        assert_eq!(slic.get_threshold(), 2);
+        assert_eq!(slic.raise_threshold(255), Ok(2));
+        assert_eq!(slic.get_threshold(), 255);
+    }
+
+    #[test]
+    fn test_enable_disable() {
+        let mut slic = slic([1, 2]);
+        slic.disable();
+        slic.pend(0);
+        assert!(slic.is_pending(0));
+        assert!(!slic.is_ready());
+        assert_eq!(slic.pop(), None);
+        assert!(slic.is_pending(0));
+
+        assert!(slic.enable());
+        slic.disable();
+        slic.pend(1);
+        assert!(slic.enable());
+        assert_eq!(slic.pop(), Some((2, 1)));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert!(!slic.enable());
+    }
+
+    fn check_set_priority_while_pending<T: TieBreak>(requeued: [u16; 3], kept: [u16; 2]) {
+        let mut slic = policy_slic::<3, T>([1, 2, 2]);
+        slic.pend(0);
+        slic.pend(1);
+        slic.set_priority(0, 3);
+        assert_eq!(slic.get_priority(0), 3);
+        // the interrupt is re-queued with its new priority
+        assert_eq!(slic.pop(), Some((3, 0)));
+        assert_eq!(slic.pop(), Some((2, 1)));
+
+        // re-queued interrupts are served as if they were pended again
+        slic.pend(0);
+        slic.pend(1);
+        slic.set_priority(0, 2);
+        slic.pend(2);
+        for interrupt in requeued {
+            assert_eq!(slic.pop(), Some((2, interrupt)));
+        }
+
+        // setting the same priority keeps the position in the queue
+        slic.pend(1);
+        slic.pend(0);
+        slic.set_priority(1, 2);
+        for interrupt in kept {
+            assert_eq!(slic.pop(), Some((2, interrupt)));
+        }
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_set_priority_while_pending() {
+        check_set_priority_while_pending::<Fifo>([1, 0, 2], [1, 0]);
+        check_set_priority_while_pending::<LowestNumberFirst>([0, 1, 2], [0, 1]);
+    }
+
+    #[test]
+    fn test_set_priority_zero_while_pending() {
+        let mut slic = slic([1, 2]);
+        slic.pend(0);
+        slic.pend(1);
+        slic.set_priority(1, 0);
+        assert!(!slic.is_pending(1));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert_eq!(slic.pop(), None);
+        // a dropped interrupt is not restored when its priority is set again
+        slic.set_priority(1, 2);
+        assert!(!slic.is_pending(1));
+        assert_eq!(slic.pop(), None);
+    }
+
+    #[test]
+    fn test_unpend() {
+        let mut slic = slic([1, 2, 1]);
+        assert!(!slic.unpend(0));
+        slic.pend(0);
+        slic.pend(1);
+        slic.pend(2);
+        assert!(slic.unpend(1));
+        assert!(!slic.is_pending(1));
+        assert!(!slic.unpend(1));
+        assert!(slic.unpend(0));
+        assert_eq!(slic.pop(), Some((1, 2)));
+        assert_eq!(slic.pop(), None);
+        // unpended interrupts can be pended again
+        slic.pend(1);
+        assert_eq!(slic.pop(), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_enable_disable_interrupt() {
+        let mut slic = slic([1, 2, 3]);
+        assert!(slic.is_interrupt_enabled(0));
+        slic.disable_interrupt(2);
+        assert!(!slic.is_interrupt_enabled(2));
+        slic.pend(0);
+        slic.pend(2);
+        // pends on disabled sources are latched
+        assert!(slic.is_pending(2));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert_eq!(slic.pop(), None);
+        assert!(slic.enable_interrupt(2));
+        assert!(slic.is_pending(2));
+        // the priority of a disabled source is kept
+        assert_eq!(slic.pop(), Some((3, 2)));
+        assert!(!slic.enable_interrupt(2));
+
+        // disabling a queued source removes it from the queue
+        slic.pend(1);
+        slic.pend(2);
+        slic.disable_interrupt(2);
+        slic.disable_interrupt(2);
+        assert_eq!(slic.pop(), Some((2, 1)));
+        assert_eq!(slic.pop(), None);
+
+        // priority changes and unpends of disabled sources are honored
+        slic.set_priority(2, 1);
+        assert!(slic.is_pending(2));
+        slic.enable_interrupt(2);
+        assert_eq!(slic.pop(), Some((1, 2)));
+        slic.pend(2);
+        slic.disable_interrupt(2);
+        assert!(slic.unpend(2));
+        assert!(!slic.enable_interrupt(2));
+        slic.pend(2);
+        slic.disable_interrupt(2);
+        slic.set_priority(2, 0);
+        assert!(!slic.is_pending(2));
+        assert!(!slic.enable_interrupt(2));
+    }
+
+    fn check_counting<T: TieBreak>(expected: [u16; 3]) {
+        let mut slic = policy_slic::<3, T>([1, 1, 2]);
+        slic.counting[0] = true;
+        slic.counting[2] = true;
+        slic.pend(0);
+        slic.pend(0);
+        slic.pend(1);
+        slic.pend(1);
+        slic.pend(0);
+        assert_eq!(slic.get_count(0), 3);
+        assert_eq!(slic.get_count(1), 1);
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert_eq!(slic.get_count(0), 2);
+        assert!(slic.is_pending(0));
+        // pend requests are served according to the tie-break policy
+        for interrupt in expected {
+            assert_eq!(slic.pop(), Some((1, interrupt)));
+        }
+        assert_eq!(slic.get_count(0), 0);
+        assert!(!slic.is_pending(0));
+        assert_eq!(slic.pop(), None);
+
+        // a source with pending requests does not preempt itself
+        slic.pend(2);
+        slic.pend(2);
+        assert_eq!(slic.pop(), Some((2, 2)));
+        assert_eq!(slic.raise_threshold(2), Ok(0));
+        assert_eq!(slic.pop(), None);
+        unsafe { slic.set_threshold(0) };
+        assert_eq!(slic.pop(), Some((2, 2)));
+        assert_eq!(slic.pop(), None);
+
+        // unpending discards all the pend requests
+        slic.pend(0);
+        slic.pend(0);
+        assert!(slic.unpend(0));
+        assert_eq!(slic.get_count(0), 0);
+        assert_eq!(slic.pop(), None);
+
+        // the counter saturates
+        slic.pend(0);
+        slic.counts[0] = u16::MAX;
+        slic.pend(0);
+        assert_eq!(slic.get_count(0), u16::MAX);
+        slic.set_priority(0, 0);
+        assert_eq!(slic.get_count(0), 0);
+        assert!(!slic.is_pending(0));
+    }
+
+    #[test]
+    fn test_counting() {
+        check_counting::<Fifo>([1, 0, 0]);
+        check_counting::<LowestNumberFirst>([0, 0, 1]);
+    }
+
+    #[test]
+    fn test_running() {
+        let mut slic = slic([1, 2]);
+        slic.pend(0);
+        assert!(!slic.is_running(0));
+        assert_eq!(slic.pop(), Some((1, 0)));
+        assert!(slic.is_running(0));
+        // a running interrupt can be pended again
+        slic.pend(0);
+        assert!(slic.is_pending(0));
+        slic.pend(1);
+        assert_eq!(slic.pop(), Some((2, 1)));
+        assert!(slic.is_running(0) && slic.is_running(1));
+        slic.complete(1);
+        assert!(!slic.is_running(1));
+        slic.complete(0);
+        assert!(!slic.is_running(0));
+        assert!(slic.is_pending(0));
+    }
+
+    #[test]
+    fn test_pop_stale_entry() {
+        let mut slic = slic([2, 1]);
+        slic.pend(0);
+        slic.pend(1);
+        // an entry whose source is no longer pending must be discarded
+        slic.pending[0] = false;
+        assert_eq!(slic.pop(), Some((1, 1)));
+        assert!(slic.queue.peek().is_none());
+
+        slic.pend(0);
+        slic.pending[0] = false;
+        assert!(slic.is_ready());
+        assert_eq!(slic.pop(), None);
+        assert!(slic.queue.peek().is_none());
+    }
+
+    /// Minimal pseudo-random number generator for the model-based tests.
+    struct XorShift(u32);
+
+    impl XorShift {
+        fn next(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn below(&mut self, n: u32) -> u32 {
+            self.next() % n
+        }
+    }
+
+    /// Naive reference scheduler with the same semantics as [`SLIC`].
+    struct Model<const N: usize> {
+        /// Serve sources with the same priority by ascending interrupt number instead of FIFO order.
+        lowest_number_first: bool,
+        enabled: bool,
+        threshold: u8,
+        priorities: [u8; N],
+        enables: [bool; N],
+        counting: [bool; N],
+        /// Pend order of each pending source.
+        queued: [Option<u64>; N],
+        /// Pend requests of each pending source.
+        counts: [u16; N],
+        /// Pend counter.
+        seq: u64,
+    }
+
+    impl<const N: usize> Model<N> {
+        fn new(lowest_number_first: bool, counting: [bool; N]) -> Self {
+            Self {
+                lowest_number_first,
+                enabled: false,
+                threshold: 0,
+                priorities: [0; N],
+                enables: [true; N],
+                counting,
+                queued: [None; N],
+                counts: [0; N],
+                seq: 0,
+            }
+        }
+
+        /// Marks a source as pending, as if it was pended right now.
+        fn queue(&mut self, interrupt: u16) {
+            self.queued[interrupt as usize] = Some(self.seq);
+            self.seq += 1;
+        }
+
+        fn pend(&mut self, interrupt: u16) {
+            let i = interrupt as usize;
+            if self.priorities[i] == 0 {
+                return;
+            }
+            if self.queued[i].is_none() {
+                self.queue(interrupt);
+            }
+            self.counts[i] = match self.counting[i] {
+                true => self.counts[i].saturating_add(1),
+                false => 1,
+            };
+        }
+
+        fn unpend(&mut self, interrupt: u16) -> bool {
+            self.counts[interrupt as usize] = 0;
+            self.queued[interrupt as usize].take().is_some()
+        }
+
+        fn set_priority(&mut self, interrupt: u16, priority: u8) {
+            let i = interrupt as usize;
+            if self.queued[i].is_some() && self.priorities[i] != priority {
+                match priority {
+                    0 => _ = self.unpend(interrupt),
+                    _ => self.queue(interrupt),
+                }
+            }
+            self.priorities[i] = priority;
+        }
+
+        fn enable_interrupt(&mut self, interrupt: u16) {
+            let i = interrupt as usize;
+            if !self.enables[i] && self.queued[i].is_some() {
+                self.queue(interrupt);
+            }
+            self.enables[i] = true;
+        }
+
+        /// Returns the highest priority pending source according to the tie-break policy.
+        fn next(&self) -> Option<(u8, u16)> {
+            (0..N)
+                .filter(|&i| self.enables[i])
+                .filter_map(|i| self.queued[i].map(|seq| (self.priorities[i], i as u16, seq)))
+                .max_by_key(|&(p, i, seq)| {
+                    if self.lowest_number_first {
+                        (p, Reverse(i as u64))
+                    } else {
+                        (p, Reverse(seq))
+                    }
+                })
+                .map(|(p, i, _)| (p, i))
+        }
+
+        fn is_ready(&self) -> bool {
+            self.enabled && matches!(self.next(), Some((p, _)) if p > self.threshold)
+        }
+
+        fn pop(&mut self) -> Option<(u8, u16)> {
+            if !self.is_ready() {
+                return None;
+            }
+            let (p, i) = self.next().unwrap();
+            self.counts[i as usize] -= 1;
+            match self.counts[i as usize] {
+                0 => self.queued[i as usize] = None,
+                _ => self.queue(i),
+            }
+            Some((p, i))
+        }
+    }
+
+    fn check_model<const N: usize, T: TieBreak>(
+        lowest_number_first: bool,
+        seed: u32,
+        steps: usize,
+    ) {
+        let mut rng = XorShift(seed);
+        let mut counting = [false; N];
+        for c in counting.iter_mut() {
+            *c = rng.below(2) == 0;
+        }
+        let mut slic = SLIC::<N, T>::new([0; N], counting);
+        let mut model = Model::<N>::new(lowest_number_first, counting);
+
+        for step in 0..steps {
+            let interrupt = rng.below(N as u32) as u16;
+            // priorities are drawn from a small range to force ties
+            let priority = rng.below(5) as u8;
+            match rng.below(10) {
+                0 => {
+                    slic.set_priority(interrupt, priority);
+                    model.set_priority(interrupt, priority);
+                }
+                1 => {
+                    unsafe { slic.set_threshold(priority) };
+                    model.threshold = priority;
+                }
+                2 => {
+                    let res = slic.raise_threshold(priority);
+                    if priority > model.threshold {
+                        assert_eq!(res, Ok(model.threshold), "step {step}");
+                        model.threshold = priority;
+                    } else {
+                        let current = model.threshold;
+                        let err = Error::ThresholdNotRaised { current };
+                        assert_eq!(res, Err(err), "step {step}");
+                    }
+                }
+                3 => {
+                    if rng.below(2) == 0 {
+                        model.enabled = true;
+                        assert_eq!(slic.enable(), model.is_ready(), "step {step}");
+                    } else {
+                        slic.disable();
+                        model.enabled = false;
+                    }
+                }
+                4 | 5 => {
+                    slic.pend(interrupt);
+                    model.pend(interrupt);
+                }
+                6 => assert_eq!(
+                    slic.unpend(interrupt),
+                    model.unpend(interrupt),
+                    "step {step}"
+                ),
+                7 => {
+                    if rng.below(2) == 0 {
+                        slic.enable_interrupt(interrupt);
+                        model.enable_interrupt(interrupt);
+                    } else {
+                        slic.disable_interrupt(interrupt);
+                        model.enables[interrupt as usize] = false;
+                    }
+                }
+                _ => assert_eq!(slic.pop(), model.pop(), "step {step}"),
+            }
+            assert_eq!(slic.is_ready(), model.is_ready(), "step {step}");
+            assert_eq!(slic.get_threshold(), model.threshold, "step {step}");
+            for i in 0..N as u16 {
+                assert_eq!(slic.is_pending(i), model.queued[i as usize].is_some());
+                assert_eq!(slic.get_count(i), model.counts[i as usize]);
+                assert_eq!(slic.get_priority(i), model.priorities[i as usize]);
+                assert_eq!(slic.is_interrupt_enabled(i), model.enables[i as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_model() {
+        for seed in 1..=64 {
+            check_model::<1, Fifo>(false, seed, 256);
+            check_model::<3, Fifo>(false, seed, 1024);
+            check_model::<8, Fifo>(false, seed, 1024);
+            check_model::<1, LowestNumberFirst>(true, seed, 256);
+            check_model::<3, LowestNumberFirst>(true, seed, 1024);
+            check_model::<8, LowestNumberFirst>(true, seed, 1024);
+        }
+    }
 }
@@ -0,0 +1,369 @@
+use super::{Fifo, LowestNumberFirst, Queue, TieBreak};
+
+impl TieBreak for Fifo {
+    type Queue<const N: usize> = FifoQueue<N>;
+}
+
+impl TieBreak for LowestNumberFirst {
+    type Queue<const N: usize> = LowestNumberFirstQueue<N>;
+}
+
+/// Marker for the end of a list.
+const NIL: u16 = u16::MAX;
+
+/// Bitmap of non-empty priority levels.
+///
+/// The highest non-empty level is found with count-leading-zeros instructions.
+#[derive(Debug)]
+pub struct Levels {
+    /// Summary bitmap. Bit `w` is set if `bits[w]` is not zero.
+    summary: u8,
+    /// Bitmap of priority levels. Bit `p % 32` of `bits[p / 32]` is set
+    /// if there is at least one interrupt source queued with priority `p`.
+    bits: [u32; 8],
+}
+
+impl Levels {
+    /// Bitmap without non-empty priority levels.
+    const EMPTY: Self = Self {
+        summary: 0,
+        bits: [0; 8],
+    };
+
+    /// Returns the highest non-empty priority level.
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        if self.summary == 0 {
+            return None;
+        }
+        let w = 7 - self.summary.leading_zeros() as usize;
+        let b = 31 - self.bits[w].leading_zeros() as usize;
+        Some((w * 32 + b) as u8)
+    }
+
+    /// Marks a priority level as non-empty.
+    #[inline]
+    fn set(&mut self, p: usize) {
+        self.bits[p / 32] |= 1 << (p % 32);
+        self.summary |= 1 << (p / 32);
+    }
+
+    /// Marks a priority level as empty.
+    #[inline]
+    fn clear(&mut self, p: usize) {
+        self.bits[p / 32] &= !(1 << (p % 32));
+        if self.bits[p / 32] == 0 {
+            self.summary &= !(1 << (p / 32));
+        }
+    }
+}
+
+/// Ready queue based on a bitmap of priority levels that serves
+/// interrupt sources with the same priority in FIFO order.
+///
+/// Each priority level keeps a linked list of queued interrupt sources.
+/// Thus, pushing, removing, and popping entries take constant time.
+#[derive(Debug)]
+pub struct FifoQueue<const N: usize> {
+    /// Bitmap of non-empty priority levels.
+    levels: Levels,
+    /// First interrupt source queued in each priority level.
+    head: [u16; 256],
+    /// Last interrupt source queued in each priority level.
+    tail: [u16; 256],
+    /// Previous interrupt source in the same priority level.
+    prev: [u16; N],
+    /// Next interrupt source in the same priority level.
+    next: [u16; N],
+}
+
+impl<const N: usize> Queue for FifoQueue<N> {
+    const EMPTY: Self = Self {
+        levels: Levels::EMPTY,
+        head: [NIL; 256],
+        tail: [NIL; 256],
+        prev: [NIL; N],
+        next: [NIL; N],
+    };
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.levels.peek()
+    }
+
+    #[inline]
+    unsafe fn push(&mut self, priority: u8, interrupt: u16) {
+        let p = priority as usize;
+        let i = interrupt as usize;
+        let after = self.tail[p];
+        self.prev[i] = after;
+        self.next[i] = NIL;
+        match after {
+            NIL => self.head[p] = interrupt,
+            after => self.next[after as usize] = interrupt,
+        }
+        self.tail[p] = interrupt;
+        self.levels.set(p);
+    }
+
+    #[inline]
+    unsafe fn remove(&mut self, priority: u8, interrupt: u16) {
+        let p = priority as usize;
+        let i = interrupt as usize;
+        let (prev, next) = (self.prev[i], self.next[i]);
+        match prev {
+            NIL => self.head[p] = next,
+            prev => self.next[prev as usize] = next,
+        }
+        match next {
+            NIL => self.tail[p] = prev,
+            next => self.prev[next as usize] = prev,
+        }
+        if self.head[p] == NIL {
+            self.levels.clear(p);
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<(u8, u16)> {
+        let priority = self.peek()?;
+        let interrupt = self.head[priority as usize];
+        // SAFETY: the interrupt source is the head of its priority level
+        unsafe { self.remove(priority, interrupt) };
+        Some((priority, interrupt))
+    }
+}
+
+/// Ready queue based on a bitmap of priority levels that serves
+/// interrupt sources with the same priority by ascending interrupt number.
+///
+/// Each priority level keeps a bitmap of queued interrupt sources, and the lowest
+/// interrupt number of a level is found with count-trailing-zeros instructions.
+/// Thus, pushing, removing, and popping entries take constant time.
+///
+/// # Note
+///
+/// This queue supports up to 960 interrupt sources.
+#[derive(Debug)]
+pub struct LowestNumberFirstQueue<const N: usize> {
+    /// Bitmap of non-empty priority levels.
+    levels: Levels,
+    /// Bitmap of queued interrupt sources. Bit `p * N + i` is set if
+    /// interrupt source `i` is queued with priority `p` (8 words per source).
+    sources: [[u32; 8]; N],
+    /// Bit `j` of `words[p]` is set if word `j` of priority level `p` is not zero.
+    words: [u32; 256],
+}
+
+impl<const N: usize> LowestNumberFirstQueue<N> {
+    /// Returns a mutable reference to a word of the bitmap of interrupt sources.
+    #[inline]
+    fn source_word(&mut self, w: usize) -> &mut u32 {
+        &mut self.sources[w / 8][w % 8]
+    }
+
+    /// Returns word `j` of priority level `p`, masked to the interrupt sources of the level.
+    /// The first and last words of a level may be shared with the adjacent levels.
+    #[inline]
+    fn level_word(&self, p: usize, j: usize) -> u32 {
+        let (first, last) = (p * N, p * N + N - 1);
+        let w = first / 32 + j;
+        let mut word = self.sources[w / 8][w % 8];
+        if j == 0 {
+            word &= u32::MAX << (first % 32);
+        }
+        if w == last / 32 {
+            word &= u32::MAX >> (31 - last % 32);
+        }
+        word
+    }
+}
+
+impl<const N: usize> Queue for LowestNumberFirstQueue<N> {
+    /// # Panics
+    ///
+    /// Evaluating this constant panics if there are more than 960 interrupt sources,
+    /// as the bitmap of a priority level must fit in 32 words.
+    const EMPTY: Self = {
+        assert!(
+            N <= 960,
+            "too many interrupt sources for the bitmap scheduler"
+        );
+        Self {
+            levels: Levels::EMPTY,
+            sources: [[0; 8]; N],
+            words: [0; 256],
+        }
+    };
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.levels.peek()
+    }
+
+    #[inline]
+    unsafe fn push(&mut self, priority: u8, interrupt: u16) {
+        let p = priority as usize;
+        let k = p * N + interrupt as usize;
+        *self.source_word(k / 32) |= 1 << (k % 32);
+        self.words[p] |= 1 << (k / 32 - p * N / 32);
+        self.levels.set(p);
+    }
+
+    #[inline]
+    unsafe fn remove(&mut self, priority: u8, interrupt: u16) {
+        let p = priority as usize;
+        let k = p * N + interrupt as usize;
+        *self.source_word(k / 32) &= !(1 << (k % 32));
+        let j = k / 32 - p * N / 32;
+        if self.level_word(p, j) == 0 {
+            self.words[p] &= !(1 << j);
+            if self.words[p] == 0 {
+                self.levels.clear(p);
+            }
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<(u8, u16)> {
+        let priority = self.peek()?;
+        let p = priority as usize;
+        let j = self.words[p].trailing_zeros() as usize;
+        let b = self.level_word(p, j).trailing_zeros() as usize;
+        let interrupt = ((p * N / 32 + j) * 32 + b - p * N) as u16;
+        // SAFETY: the interrupt source is queued with the given priority
+        unsafe { self.remove(priority, interrupt) };
+        Some((priority, interrupt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the bitmap of non-empty priority levels of a queue.
+    trait LevelsOf {
+        fn levels(&self) -> &Levels;
+    }
+
+    impl<const N: usize> LevelsOf for FifoQueue<N> {
+        fn levels(&self) -> &Levels {
+            &self.levels
+        }
+    }
+
+    impl<const N: usize> LevelsOf for LowestNumberFirstQueue<N> {
+        fn levels(&self) -> &Levels {
+            &self.levels
+        }
+    }
+
+    fn check_levels<Q: Queue + LevelsOf>() {
+        let mut queue = Q::EMPTY;
+        assert_eq!(queue.peek(), None);
+        unsafe {
+            queue.push(1, 0);
+            queue.push(32, 1);
+            queue.push(255, 2);
+            queue.push(31, 3);
+        }
+        assert_eq!(queue.levels().summary, 0b1000_0011);
+        assert_eq!(queue.peek(), Some(255));
+        assert_eq!(queue.pop(), Some((255, 2)));
+        assert_eq!(queue.levels().summary, 0b0000_0011);
+        assert_eq!(queue.pop(), Some((32, 1)));
+        assert_eq!(queue.levels().summary, 0b0000_0001);
+        assert_eq!(queue.pop(), Some((31, 3)));
+        assert_eq!(queue.pop(), Some((1, 0)));
+        assert!(queue.peek().is_none());
+        assert_eq!(queue.levels().bits, [0; 8]);
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_levels() {
+        check_levels::<FifoQueue<4>>();
+        check_levels::<LowestNumberFirstQueue<4>>();
+    }
+
+    fn check_same_level<Q: Queue>(expected: [u16; 3]) -> Q {
+        let mut queue = Q::EMPTY;
+        unsafe {
+            queue.push(3, 2);
+            queue.push(3, 0);
+            queue.push(3, 3);
+        }
+        for interrupt in expected {
+            assert_eq!(queue.pop(), Some((3, interrupt)));
+        }
+        // the level can be reused after being emptied
+        unsafe { queue.push(3, 1) };
+        assert_eq!(queue.pop(), Some((3, 1)));
+        assert!(queue.peek().is_none());
+        queue
+    }
+
+    #[test]
+    fn test_same_level() {
+        let queue = check_same_level::<FifoQueue<4>>([2, 0, 3]);
+        assert_eq!((queue.head[3], queue.tail[3]), (NIL, NIL));
+        let queue = check_same_level::<LowestNumberFirstQueue<4>>([0, 2, 3]);
+        assert_eq!(queue.words[3], 0);
+    }
+
+    fn check_remove<Q: Queue + LevelsOf>() {
+        let mut queue = Q::EMPTY;
+        unsafe {
+            queue.push(3, 0);
+            queue.push(3, 1);
+            queue.push(3, 2);
+            queue.push(40, 3);
+            queue.remove(3, 1); // middle
+            queue.remove(40, 3); // only entry of its level
+        }
+        assert_eq!(queue.levels().bits[1], 0);
+        assert_eq!(queue.levels().summary, 0b0000_0001);
+        unsafe {
+            queue.remove(3, 2); // tail
+            queue.push(3, 1);
+            queue.remove(3, 0); // head
+        }
+        assert_eq!(queue.pop(), Some((3, 1)));
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.levels().summary, 0);
+    }
+
+    #[test]
+    fn test_remove() {
+        check_remove::<FifoQueue<4>>();
+        check_remove::<LowestNumberFirstQueue<4>>();
+    }
+
+    #[test]
+    fn test_shared_words() {
+        // with 40 sources, levels 3 and 4 share the word of bits 128..160
+        let mut queue = LowestNumberFirstQueue::<40>::EMPTY;
+        unsafe {
+            queue.push(4, 39);
+            queue.push(3, 39);
+            queue.push(4, 0);
+            queue.push(3, 0);
+            queue.push(4, 31);
+            queue.push(3, 33);
+        }
+        let expected = [(4, 0), (4, 31), (4, 39), (3, 0), (3, 33), (3, 39)];
+        for entry in expected {
+            assert_eq!(queue.pop(), Some(entry));
+        }
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.words, [0; 256]);
+        assert_eq!(queue.sources, [[0; 8]; 40]);
+        unsafe {
+            queue.push(3, 39);
+            queue.push(4, 0);
+            queue.remove(4, 0);
+        }
+        assert_eq!(queue.pop(), Some((3, 39)));
+        assert_eq!(queue.levels.summary, 0);
+    }
+}
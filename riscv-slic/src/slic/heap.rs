@@ -0,0 +1,150 @@
+use super::{Fifo, LowestNumberFirst, TieBreak};
+use core::cmp::Reverse;
+use heapless::binary_heap::{BinaryHeap, Max};
+
+impl TieBreak for Fifo {
+    type Queue<const N: usize> = Queue<N, false>;
+}
+
+impl TieBreak for LowestNumberFirst {
+    type Queue<const N: usize> = Queue<N, true>;
+}
+
+/// Ready queue based on a binary heap.
+///
+/// Pushing and popping entries are O(log N) operations.
+/// Removing an arbitrary entry requires rebuilding the heap.
+/// If `LNF` is `true`, entries with the same priority are popped by ascending
+/// interrupt number instead of FIFO order.
+#[derive(Debug)]
+pub struct Queue<const N: usize, const LNF: bool> {
+    /// Binary heap with queued interrupt sources.
+    /// Each entry contains the priority, the tie-break order, and the interrupt number.
+    heap: BinaryHeap<(u8, Reverse<u32>, u16), Max, N>,
+    /// Pend counter used to serve interrupts with the same priority in FIFO order.
+    seq: u32,
+}
+
+impl<const N: usize, const LNF: bool> super::Queue for Queue<N, LNF> {
+    const EMPTY: Self = Self {
+        heap: BinaryHeap::new(),
+        seq: 0,
+    };
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.heap.peek().map(|&(p, _, _)| p)
+    }
+
+    #[inline]
+    unsafe fn push(&mut self, priority: u8, interrupt: u16) {
+        let order = self.order(interrupt);
+        // SAFETY: the queue has room for all the interrupt sources
+        self.heap
+            .push_unchecked((priority, Reverse(order), interrupt));
+    }
+
+    #[inline]
+    unsafe fn remove(&mut self, _priority: u8, interrupt: u16) {
+        let entries = core::mem::replace(&mut self.heap, BinaryHeap::new()).into_vec();
+        for entry in entries.into_iter().filter(|&(_, _, i)| i != interrupt) {
+            // SAFETY: the new heap has the same capacity as the previous one
+            self.heap.push_unchecked(entry);
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<(u8, u16)> {
+        self.heap.pop().map(|(p, _, i)| (p, i))
+    }
+}
+
+impl<const N: usize, const LNF: bool> Queue<N, LNF> {
+    /// Returns the tie-break order of a new entry of the queue.
+    /// Entries with the same priority and lower order are served first.
+    #[inline]
+    fn order(&mut self, interrupt: u16) -> u32 {
+        if LNF {
+            return interrupt as u32;
+        }
+        if self.seq == u32::MAX {
+            self.rebase();
+        }
+        let order = self.seq;
+        self.seq += 1;
+        order
+    }
+
+    /// Renumbers the queued entries to prevent the pend counter from overflowing.
+    /// The relative order of the queued entries is preserved.
+    #[cold]
+    fn rebase(&mut self) {
+        let mut heap = BinaryHeap::new();
+        let mut seq = 0;
+        // entries are popped in service order, so increasing numbers preserve FIFO order
+        while let Some((priority, _, interrupt)) = self.heap.pop() {
+            // SAFETY: the new heap has the same capacity as the previous one
+            unsafe { heap.push_unchecked((priority, Reverse(seq), interrupt)) };
+            seq += 1;
+        }
+        self.heap = heap;
+        self.seq = seq;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Queue as _;
+    use super::*;
+
+    #[test]
+    fn test_rebase() {
+        let mut queue = Queue::<4, false>::EMPTY;
+        queue.seq = u32::MAX - 2;
+        unsafe {
+            queue.push(1, 2);
+            queue.push(2, 3);
+            // the pend counter is about to overflow here, so queued entries are renumbered
+            queue.push(1, 1);
+            queue.push(1, 0);
+        }
+        assert_eq!(queue.seq, 4);
+        assert_eq!(queue.pop(), Some((2, 3)));
+        assert_eq!(queue.pop(), Some((1, 2)));
+        assert_eq!(queue.pop(), Some((1, 1)));
+        assert_eq!(queue.pop(), Some((1, 0)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut queue = Queue::<4, false>::EMPTY;
+        unsafe {
+            queue.push(1, 0);
+            queue.push(2, 1);
+            queue.push(1, 2);
+            queue.remove(1, 0);
+            queue.remove(2, 1);
+        }
+        assert_eq!(queue.pop(), Some((1, 2)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_lowest_number_first() {
+        let mut queue = Queue::<4, true>::EMPTY;
+        unsafe {
+            queue.push(1, 2);
+            queue.push(1, 0);
+            queue.push(2, 3);
+            queue.push(1, 1);
+        }
+        // the pend counter is not used
+        assert_eq!(queue.seq, 0);
+        assert_eq!(queue.pop(), Some((2, 3)));
+        assert_eq!(queue.pop(), Some((1, 0)));
+        assert_eq!(queue.pop(), Some((1, 1)));
+        assert_eq!(queue.pop(), Some((1, 2)));
+        assert_eq!(queue.pop(), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Software timer queue that pends software interrupts at given deadlines.
+//!
+//! A [`TimerQueue`] owns the comparator of a machine timer (i.e., `mtimecmp`) and keeps a
+//! queue of `(deadline, interrupt)` entries sorted by deadline. The comparator is always set
+//! to the earliest deadline in the queue. When the timer interrupt fires, the handler must call
+//! [`TimerQueue::on_interrupt`] to pend the software interrupts with expired deadlines.
+//! When the queue is empty, the comparator is set to `u64::MAX` to disarm the timer interrupt.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static TIMER: TimerQueue<Mtimer, SoftwareInterrupt, 4> = TimerQueue::new(Mtimer);
+//!
+//! #[riscv_rt::core_interrupt(CoreInterrupt::MachineTimer)]
+//! fn machine_timer() {
+//!     TIMER.on_interrupt();
+//! }
+//! ```
+
+use crate::{Error, InterruptNumber};
+use core::cell::RefCell;
+use critical_section::Mutex;
+use heapless::Vec;
+
+/// Trait for machine timers that drive a [`TimerQueue`].
+pub trait MachineTimer {
+    /// Returns the current value of the timer counter (i.e., `mtime`).
+    fn now(&self) -> u64;
+
+    /// Sets the timer comparator (i.e., `mtimecmp`).
+    /// The timer interrupt must fire once the counter reaches this value.
+    ///
+    /// A `deadline` of `u64::MAX` disarms the timer, as the counter never reaches it.
+    /// On RV32 targets, implementations that write the comparator as two 32-bit halves
+    /// must first set the low half to `u32::MAX` to avoid spurious timer interrupts.
+    fn set_compare(&self, deadline: u64);
+}
+
+/// Entry of the timer queue.
+#[derive(Clone, Copy)]
+struct Entry<I> {
+    /// Instant at which the software interrupt is pended
+    deadline: u64,
+    /// Period of the entry (0 for one-shot entries)
+    period: u64,
+    /// Software interrupt to pend
+    interrupt: I,
+}
+
+/// Queue of software interrupts to be pended at given deadlines.
+///
+/// The queue can hold up to `N` entries at the same time.
+pub struct TimerQueue<T, I, const N: usize> {
+    timer: T,
+    queue: Mutex<RefCell<Vec<Entry<I>, N>>>,
+}
+
+impl<T: MachineTimer, I: InterruptNumber, const N: usize> TimerQueue<T, I, N> {
+    /// Creates a new, empty timer queue that owns the comparator of `timer`.
+    #[inline]
+    pub const fn new(timer: T) -> Self {
+        Self {
+            timer,
+            queue: Mutex::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Returns the current value of the timer counter.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.timer.now()
+    }
+
+    /// Pends a software interrupt once the timer counter reaches `deadline`.
+    ///
+    /// Software interrupts with past deadlines are pended as soon as the timer interrupt fires.
+    /// If the queue is full, it returns [`Error::QueueFull`].
+    #[inline]
+    pub fn pend_at(&self, deadline: u64, interrupt: I) -> Result<(), Error> {
+        self.schedule(Entry {
+            deadline,
+            period: 0,
+            interrupt,
+        })
+    }
+
+    /// Pends a software interrupt after `duration` timer ticks.
+    ///
+    /// If the queue is full, it returns [`Error::QueueFull`].
+    #[inline]
+    pub fn pend_after(&self, duration: u64, interrupt: I) -> Result<(), Error> {
+        self.pend_at(self.now().saturating_add(duration), interrupt)
+    }
+
+    /// Pends a software interrupt every `period` timer ticks, starting after one period.
+    ///
+    /// If the queue is full, it returns [`Error::QueueFull`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `period` is 0.
+    #[inline]
+    pub fn pend_periodic(&self, period: u64, interrupt: I) -> Result<(), Error> {
+        assert!(period > 0, "period must be greater than 0");
+        self.schedule(Entry {
+            deadline: self.now().saturating_add(period),
+            period,
+            interrupt,
+        })
+    }
+
+    /// Removes all the scheduled entries of a software interrupt.
+    /// Returns `true` if the software interrupt was scheduled.
+    pub fn cancel(&self, interrupt: I) -> bool {
+        let number = interrupt.number();
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow_ref_mut(cs);
+            let len = queue.len();
+            queue.retain(|entry| entry.interrupt.number() != number);
+            self.update_compare(&queue);
+            queue.len() != len
+        })
+    }
+
+    /// Pends all the software interrupts with expired deadlines and reschedules periodic entries.
+    ///
+    /// Periodic entries that fell behind are pended once, and their missed periods are skipped.
+    /// If the queue becomes empty, the comparator is set to `u64::MAX`.
+    ///
+    /// This function must be called from the timer interrupt handler.
+    pub fn on_interrupt(&self) {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow_ref_mut(cs);
+            let now = self.timer.now();
+            while queue.first().is_some_and(|entry| entry.deadline <= now) {
+                let mut entry = queue.remove(0);
+                crate::pend(entry.interrupt);
+                // one-shot entries have a period of 0
+                if let Some(missed) = (now - entry.deadline).checked_div(entry.period) {
+                    // skip the missed periods, so the new deadline is in the future
+                    let periods = missed + 1;
+                    let delay = entry.period.saturating_mul(periods);
+                    entry.deadline = entry.deadline.saturating_add(delay);
+                    // we removed an entry, so there is room for the periodic entry
+                    Self::insert(&mut queue, entry);
+                }
+            }
+            self.update_compare(&queue);
+        });
+    }
+
+    /// Inserts an entry in the queue and updates the comparator.
+    fn schedule(&self, entry: Entry<I>) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow_ref_mut(cs);
+            if queue.is_full() {
+                return Err(Error::QueueFull);
+            }
+            Self::insert(&mut queue, entry);
+            self.update_compare(&queue);
+            Ok(())
+        })
+    }
+
+    /// Inserts an entry after all the entries with the same or earlier deadline.
+    fn insert(queue: &mut Vec<Entry<I>, N>, entry: Entry<I>) {
+        let index = queue.partition_point(|e| e.deadline <= entry.deadline);
+        // the caller makes sure that the queue is not full
+        let _ = queue.insert(index, entry);
+    }
+
+    /// Sets the comparator to the earliest deadline of the queue, or to `u64::MAX` if it is empty.
+    fn update_compare(&self, queue: &Vec<Entry<I>, N>) {
+        let deadline = queue.first().map_or(u64::MAX, |entry| entry.deadline);
+        self.timer.set_compare(deadline);
+    }
+}
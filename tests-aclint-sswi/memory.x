@@ -0,0 +1,12 @@
+/* QEMU virt machine: S-mode payloads are loaded by OpenSBI right after the firmware */
+MEMORY
+{
+  RAM : ORIGIN = 0x80400000, LENGTH = 16M
+}
+
+REGION_ALIAS("REGION_TEXT", RAM);
+REGION_ALIAS("REGION_RODATA", RAM);
+REGION_ALIAS("REGION_DATA", RAM);
+REGION_ALIAS("REGION_BSS", RAM);
+REGION_ALIAS("REGION_HEAP", RAM);
+REGION_ALIAS("REGION_STACK", RAM);
@@ -0,0 +1,72 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+/// Minimal PAC for the QEMU `virt` machine running an S-mode kernel.
+mod pac {
+    pub mod interrupt {
+        pub use riscv::interrupt::supervisor::Interrupt as CoreInterrupt;
+
+        /// HARTs of the QEMU `virt` machine (with `-smp 2`).
+        /// Each HART has its own `SETSSIP` register at `base + 4 * hart_id`.
+        #[riscv::pac_enum(unsafe HartIdNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum Hart {
+            H0 = 0,
+            H1 = 1,
+        }
+    }
+}
+
+// generate SLIC code for this example (QEMU virt machine with aclint=on).
+// The kernel runs on HART 0 (riscv-rt parks the other HARTs), so the SLIC writes to its SETSSIP register.
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
+    backend = [base = 0x2F0_0000, hart_id = H0]
+);
+use slic::resources::{context, COUNTER};
+use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    // SETSSIP only signals an edge, so the SLIC clears `sip.SSIP` before dispatching handlers
+    assert!(!riscv::register::sip::read().ssoft());
+    COUNTER::lock(&cx, |counter| *counter += 1);
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
+/// Each pend is a write to the `SETSSIP` register of HART 0.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftLow task (low priority).
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    // SoftHigh preempted SoftMedium before SoftLow started
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    assert!(count > 0);
+}
+
+#[riscv_rt::entry]
+fn main() -> ! {
+    // make sure that interrupts are off
+    riscv_slic::disable();
+    // enables the SLIC, `sie.SSIE`, and `sstatus.SIE`. The M-mode firmware (e.g., OpenSBI)
+    // must delegate supervisor software interrupts to S-mode via `mideleg`
+    unsafe { riscv_slic::enable() };
+
+    loop {
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        for _ in 0..100_000 {
+            riscv::asm::nop();
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+/// Minimal PAC for an M-mode target with a CLIC.
+mod pac {
+    pub mod interrupt {
+        /// Core interrupts of the target, including the local interrupt lines of the CLIC.
+        #[riscv::pac_enum(unsafe CoreInterruptNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum CoreInterrupt {
+            MachineSoft = 3,
+            MachineTimer = 7,
+            MachineExternal = 11,
+            Local0 = 16,
+            Local1 = 17,
+            Local2 = 18,
+        }
+    }
+}
+
+// generate SLIC code for this example (CLIC with 2 level bits, so each priority is a CLIC level)
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    counting = [SoftLow],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
+    backend = [
+        base = 0x0280_0000,
+        level_bits = 2,
+        lines = [SoftLow = Local0, SoftMedium = Local1, SoftHigh = Local2]
+    ]
+);
+use slic::resources::{context, COUNTER};
+use slic::{Priority, SoftwareInterrupt}; // Re-export of automatically generated enums in previous macro
+
+/// Handler for SoftHigh task (high priority). Its CLIC line has level `0b11` in `clicintctl`.
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    // mintthresh is 0, but the threshold includes the level of the running handler
+    assert_eq!(riscv_slic::get_threshold(), Priority::P3 as u8);
+    COUNTER::lock(&cx, |counter| *counter += 1);
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends SoftLow twice and SoftHigh once.
+/// SoftHigh preempts it right away, as its CLIC line has a higher level.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftLow task (low priority). As SoftLow is counting, it runs once per pend
+/// request: the SLIC sets its `clicintip` register again when the first run completes.
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    // SoftHigh preempted SoftMedium before SoftLow started
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    assert!(count > 0);
+}
+
+#[riscv_rt::entry]
+fn main() -> ! {
+    // make sure that interrupts are off
+    riscv_slic::disable();
+    // the SLIC latches this request until it configures the CLIC lines
+    riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+    // sets mtvt and mtvec to CLIC mode, configures the CLIC lines, and sets the latched clicintip
+    unsafe { riscv_slic::enable() };
+
+    loop {
+        for _ in 0..100_000 {
+            riscv::asm::nop();
+        }
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+    }
+}
@@ -0,0 +1,101 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+use hifive1::{
+    hal::{
+        e310x::{self, CLINT},
+        prelude::*,
+        DeviceResources,
+    },
+    pin, sprintln,
+};
+use riscv_slic::timer::{MachineTimer, TimerQueue};
+
+// generate SLIC code for this example
+riscv_slic::codegen!(
+    pac = e310x,
+    swi = [Blink = 1, Report = 2],
+    backend = [hart_id = H0]
+);
+use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
+
+/// Machine timer of the CLINT peripheral.
+struct Mtimer;
+
+impl MachineTimer for Mtimer {
+    fn now(&self) -> u64 {
+        CLINT::mtimer().mtime.read()
+    }
+
+    fn set_compare(&self, deadline: u64) {
+        CLINT::mtimecmp0().write(deadline);
+    }
+}
+
+/// Timer queue that owns the comparator of the CLINT.
+static TIMER: TimerQueue<Mtimer, SoftwareInterrupt, 2> = TimerQueue::new(Mtimer);
+
+/// HW handler for MachineTimer interrupts triggered by CLINT.
+#[riscv_rt::core_interrupt(CoreInterrupt::MachineTimer)]
+fn machine_timer() {
+    TIMER.on_interrupt();
+}
+
+/// Handler for Blink task. It runs periodically.
+#[riscv_slic::handler(Blink)]
+fn blink() {
+    sprintln!("Blink ({})", TIMER.now());
+}
+
+/// Handler for Report task. It runs once, and then it schedules itself again.
+#[riscv_slic::handler(Report)]
+fn report() {
+    sprintln!("  Report ({})", TIMER.now());
+    TIMER
+        .pend_after(3 * CLINT::freq() as u64, SoftwareInterrupt::Report)
+        .unwrap();
+}
+
+#[riscv_rt::entry]
+fn main() -> ! {
+    let resources = DeviceResources::take().unwrap();
+    let peripherals = resources.peripherals;
+
+    let clocks = hifive1::configure_clocks(peripherals.PRCI, peripherals.AONCLK, 64.mhz().into());
+    let gpio = resources.pins;
+
+    // Configure UART for stdout
+    hifive1::stdout::configure(
+        peripherals.UART0,
+        pin!(gpio, uart0_tx),
+        pin!(gpio, uart0_rx),
+        115_200.bps(),
+        clocks,
+    );
+
+    sprintln!("Configuring CLINT...");
+    CLINT::disable();
+    CLINT::mtimer().mtime.write(0);
+
+    sprintln!("Configuring SLIC...");
+    riscv_slic::disable();
+    TIMER
+        .pend_periodic(CLINT::freq() as u64, SoftwareInterrupt::Blink)
+        .unwrap();
+    TIMER
+        .pend_at(2 * CLINT::freq() as u64, SoftwareInterrupt::Report)
+        .unwrap();
+
+    sprintln!("Enabling interrupts...");
+    unsafe {
+        CLINT::mtimer_enable();
+        riscv_slic::enable();
+    }
+
+    loop {
+        riscv_slic::riscv::asm::wfi();
+    }
+}
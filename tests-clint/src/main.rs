@@ -16,9 +16,11 @@ use hifive1::{
 // generate SLIC code for this example
 riscv_slic::codegen!(
     pac = e310x,
-    swi = [SoftLow, SoftMedium, SoftHigh],
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
     backend = [hart_id = H0]
 );
+use slic::resources::{context, COUNTER};
 use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
 
 /// HW handler for MachineTimer interrupts triggered by CLINT.
@@ -29,17 +31,16 @@ fn machine_timer() {
 }
 
 /// Handler for SoftHigh task (high priority).
-#[allow(non_snake_case)]
-#[no_mangle]
-fn SoftHigh() {
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
     sprintln!("    start SoftHigh");
+    COUNTER::lock(&cx, |counter| *counter += 1);
     sprintln!("    stop SoftHigh");
 }
 
 /// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
-#[allow(non_snake_case)]
-#[no_mangle]
-fn SoftMedium() {
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
     sprintln!("  start SoftMedium");
     riscv_slic::pend(SoftwareInterrupt::SoftLow);
     sprintln!("  middle SoftMedium");
@@ -48,11 +49,11 @@ fn SoftMedium() {
 }
 
 /// Handler for SoftLow task (low priority).
-#[allow(non_snake_case)]
-#[no_mangle]
-fn SoftLow() {
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
     sprintln!("start SoftLow");
-    sprintln!("stop SoftLow");
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    sprintln!("stop SoftLow ({})", count);
 }
 
 #[riscv_rt::entry]
@@ -82,12 +83,6 @@ fn main() -> ! {
     sprintln!("Configuring SLIC...");
     // make sure that interrupts are off
     riscv_slic::disable();
-    // Set priorities
-    unsafe {
-        riscv_slic::set_priority(SoftwareInterrupt::SoftLow, 1); // low priority
-        riscv_slic::set_priority(SoftwareInterrupt::SoftMedium, 2); // medium priority
-        riscv_slic::set_priority(SoftwareInterrupt::SoftHigh, 3); // high priority
-    }
 
     sprintln!("Enabling interrupts...");
     unsafe {
@@ -0,0 +1,81 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+/// Minimal PAC for the QEMU `virt` machine (with `aia=aplic-imsic`) running an S-mode kernel.
+mod pac {
+    pub mod interrupt {
+        pub use riscv::interrupt::supervisor::Interrupt as CoreInterrupt;
+
+        /// Interrupt identities of the supervisor interrupt file. We assume that the
+        /// APLIC forwards the interrupts of its sources as MSIs with the same identity.
+        #[riscv::pac_enum(unsafe ExternalInterruptNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum ExternalInterrupt {
+            Uart0 = 10,
+        }
+    }
+}
+
+// generate SLIC code for this example (supervisor interrupt file of HART 0 of the QEMU virt machine)
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
+    backend = [file = supervisor, address = 0x2800_0000, identity = 1]
+);
+use slic::resources::{context, COUNTER};
+use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
+
+/// Enables an interrupt identity in the `eie*` registers of the supervisor interrupt file.
+unsafe fn enable_identity(identity: u16) {
+    let (eie, mask) = riscv_slic::imsic::eie(identity);
+    core::arch::asm!("csrw siselect, {}", in(reg) eie);
+    core::arch::asm!("csrs sireg, {}", in(reg) mask);
+}
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    COUNTER::lock(&cx, |counter| *counter += 1);
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftLow task (low priority).
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    // SoftHigh preempted SoftMedium before SoftLow started
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    assert!(count > 0);
+}
+
+/// Handler for the UART. The SLIC claims its identity via `stopei` and hands it over to the PAC.
+#[riscv_rt::external_interrupt(pac::interrupt::ExternalInterrupt::Uart0)]
+fn uart0() {
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+#[riscv_rt::entry]
+fn main() -> ! {
+    // make sure that interrupts are off
+    riscv_slic::disable();
+    // supervisor external interrupts are shared by the SLIC (identity 1) and the UART (identity 10)
+    unsafe { enable_identity(pac::interrupt::ExternalInterrupt::Uart0 as u16) };
+    // enables eidelivery, eithreshold, the eie* bit of identity 1, and `sie.SEIE`
+    unsafe { riscv_slic::enable() };
+
+    loop {
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        for _ in 0..100_000 {
+            riscv::asm::nop();
+        }
+    }
+}
@@ -15,7 +15,7 @@ use hifive1::{
 };
 
 // generate SLIC code for this example
-riscv_slic_codegen!(pac = e310x, swi = [Soft0, Soft1, Soft2]);
+riscv_slic_codegen!(pac = e310x, swi = [Soft0 = 1, Soft1 = 2, Soft2 = 3]);
 use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
 
 /// HW handler for MachineTimer interrupts triggered by CLINT.
@@ -92,12 +92,6 @@ fn main() -> ! {
     sprintln!("Configuring SLIC...");
     // make sure that interrupts are off
     riscv_slic::disable();
-    // Set priorities
-    unsafe {
-        riscv_slic::set_priority(SoftwareInterrupt::Soft0, 1); // low priority
-        riscv_slic::set_priority(SoftwareInterrupt::Soft1, 2); // medium priority
-        riscv_slic::set_priority(SoftwareInterrupt::Soft2, 3); // high priority
-    }
 
     sprintln!("Enabling interrupts...");
     unsafe {
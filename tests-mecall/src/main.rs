@@ -14,7 +14,10 @@ use hifive1::{
 };
 
 // generate SLIC code for this example
-riscv_slic::codegen!(pac = e310x, swi = [SoftLow, SoftMedium, SoftHigh]);
+riscv_slic::codegen!(
+    pac = e310x,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3]
+);
 use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
 
 /// HW handler for MachineTimer interrupts triggered by CLINT.
@@ -78,12 +81,6 @@ fn main() -> ! {
     sprintln!("Configuring SLIC...");
     // make sure that interrupts are off
     riscv_slic::disable();
-    // Set priorities
-    unsafe {
-        riscv_slic::set_priority(SoftwareInterrupt::SoftLow, 1); // low priority
-        riscv_slic::set_priority(SoftwareInterrupt::SoftMedium, 2); // medium priority
-        riscv_slic::set_priority(SoftwareInterrupt::SoftHigh, 3); // high priority
-    }
 
     sprintln!("Enabling interrupts...");
     unsafe {
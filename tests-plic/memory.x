@@ -0,0 +1,17 @@
+/* QEMU virt machine: M-mode programs are loaded at the beginning of the RAM */
+MEMORY
+{
+  RAM : ORIGIN = 0x80000000, LENGTH = 16M
+}
+
+REGION_ALIAS("REGION_TEXT", RAM);
+REGION_ALIAS("REGION_RODATA", RAM);
+REGION_ALIAS("REGION_DATA", RAM);
+REGION_ALIAS("REGION_BSS", RAM);
+REGION_ALIAS("REGION_HEAP", RAM);
+REGION_ALIAS("REGION_STACK", RAM);
+
+/* External interrupt sources of the local PAC */
+PROVIDE(Uart0 = DefaultHandler);
+PROVIDE(Rtc = DefaultHandler);
+PROVIDE(Spare = DefaultHandler);
@@ -0,0 +1,112 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+/// Minimal PAC for an M-mode program on a target with the memory map of the QEMU `virt` machine.
+///
+/// Note that the PLIC of the target must have software-settable pending bits.
+/// The PLIC of QEMU ignores writes to its pending array, so this example only builds for it.
+mod pac {
+    riscv_slic::riscv_peripheral::plic_codegen!(base 0x0C00_0000,);
+
+    pub mod interrupt {
+        pub use riscv::interrupt::machine::Interrupt as CoreInterrupt;
+
+        /// External interrupt sources of the PLIC.
+        #[riscv::pac_enum(unsafe ExternalInterruptNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum ExternalInterrupt {
+            Uart0 = 10,
+            Rtc = 11,
+            Spare = 31,
+        }
+
+        /// Priority levels of the PLIC.
+        #[riscv::pac_enum(unsafe PriorityNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum Priority {
+            P0 = 0,
+            P1 = 1,
+            P2 = 2,
+            P3 = 3,
+            P4 = 4,
+            P5 = 5,
+            P6 = 6,
+            P7 = 7,
+        }
+
+        /// HARTs of the target (i.e., PLIC contexts).
+        #[riscv::pac_enum(unsafe HartIdNumber)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum Hart {
+            H0 = 0,
+        }
+    }
+}
+
+// generate SLIC code for this example (spare source 31 of the PLIC)
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
+    backend = [plic = pac::PLIC, context = H0, source = Spare]
+);
+use slic::resources::{context, COUNTER};
+use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    COUNTER::lock(&cx, |counter| *counter += 1);
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
+/// Each pend sets the pending bit of the `Spare` source in the PLIC.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftLow task (low priority).
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    // SoftHigh preempted SoftMedium before SoftLow started
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    assert!(count > 0);
+}
+
+/// Handler for the UART. The SLIC hands it over to the PAC when it claims its PLIC source,
+/// and completes the source once the handler returns.
+#[riscv_rt::external_interrupt(pac::interrupt::ExternalInterrupt::Uart0)]
+fn uart0() {
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+#[riscv_rt::entry]
+fn main() -> ! {
+    use pac::interrupt::{ExternalInterrupt, Hart, Priority};
+
+    // make sure that interrupts are off
+    riscv_slic::disable();
+    // machine external interrupts are shared by the SLIC (Spare) and the UART (Uart0).
+    // The SLIC only configures its own source, so we configure the UART source here.
+    // It gets a lower priority than the Spare source, which the SLIC sets to the highest one.
+    unsafe {
+        pac::PLIC::priorities().set_priority(ExternalInterrupt::Uart0, Priority::P1);
+        pac::PLIC::ctx(Hart::H0)
+            .enables()
+            .enable(ExternalInterrupt::Uart0);
+    }
+    // enables the Spare source with priority P7 and `mie.MEIE`
+    unsafe { riscv_slic::enable() };
+
+    loop {
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        for _ in 0..100_000 {
+            riscv::asm::nop();
+        }
+    }
+}
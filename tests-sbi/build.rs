@@ -0,0 +1,11 @@
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    // Put the memory layout of the QEMU virt machine where the linker can find it
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::copy("memory.x", out_dir.join("memory.x")).unwrap();
+    println!("cargo:rustc-link-search={}", out_dir.display());
+    println!("cargo:rustc-link-arg=-Tmemory.x");
+    println!("cargo:rustc-link-arg=-Tlink.x");
+    println!("cargo:rerun-if-changed=memory.x");
+}
@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+
+extern crate panic_halt;
+extern crate riscv_slic;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Minimal PAC for the QEMU `virt` machine running an S-mode kernel.
+mod pac {
+    pub mod interrupt {
+        pub use riscv::interrupt::supervisor::Interrupt as CoreInterrupt;
+    }
+}
+
+/// HART that runs the kernel. OpenSBI passes its ID to the kernel in `a0`.
+static HART_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the ID of the HART that runs the kernel, so the SLIC sends its IPIs to itself.
+fn hart_id() -> usize {
+    HART_ID.load(Ordering::Relaxed)
+}
+
+// generate SLIC code for this example (QEMU virt machine with OpenSBI)
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [COUNTER: u32 = 0 => [SoftLow, SoftHigh]],
+    backend = [hart_id = current(crate::hart_id)]
+);
+use slic::resources::{context, COUNTER};
+use slic::SoftwareInterrupt; // Re-export of automatically generated enum of interrupts in previous macro
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    COUNTER::lock(&cx, |counter| *counter += 1);
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
+/// Each pend is an `sbi_send_ipi` call to the current HART.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftLow task (low priority).
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    // SoftHigh preempted SoftMedium before SoftLow started
+    let count = COUNTER::lock(&cx, |counter| *counter);
+    assert!(count > 0);
+}
+
+#[riscv_rt::entry]
+fn main(hart_id: usize) -> ! {
+    HART_ID.store(hart_id, Ordering::Relaxed);
+
+    // make sure that interrupts are off
+    riscv_slic::disable();
+    // enables the SLIC, `sie.SSIE`, and `sstatus.SIE`. OpenSBI delegates IPIs to S-mode
+    unsafe { riscv_slic::enable() };
+
+    loop {
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        for _ in 0..100_000 {
+            riscv::asm::nop();
+        }
+    }
+}
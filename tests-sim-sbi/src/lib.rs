@@ -0,0 +1,57 @@
+//! Stand-in SBI implementation for testing the SBI backend on the host-side simulated hart.
+//!
+//! Run the tests with `cargo test --package tests-sim-sbi --target <host target>`.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use riscv_slic::sbi::{EID_IPI, FID_SEND_IPI};
+use std::sync::Mutex;
+
+/// `SBI_ERR_NOT_SUPPORTED` error code.
+pub const ERR_NOT_SUPPORTED: isize = -2;
+/// `SBI_ERR_INVALID_PARAM` error code.
+pub const ERR_INVALID_PARAM: isize = -3;
+
+/// Number of HARTs of the simulated platform.
+pub const N_HARTS: usize = 3;
+
+/// HART that is running the tests.
+static CURRENT_HART: AtomicUsize = AtomicUsize::new(0);
+
+/// Arguments of the environment calls received by the stand-in.
+static ECALLS: Mutex<Vec<(usize, usize, usize, usize)>> = Mutex::new(Vec::new());
+
+/// Returns the HART that is running the tests.
+pub fn hart_id() -> usize {
+    CURRENT_HART.load(Ordering::SeqCst)
+}
+
+/// Moves the tests to another HART.
+pub fn set_hart_id(hart_id: usize) {
+    CURRENT_HART.store(hart_id, Ordering::SeqCst);
+}
+
+/// Returns the arguments of all the environment calls received so far and clears the record.
+pub fn take_ecalls() -> Vec<(usize, usize, usize, usize)> {
+    core::mem::take(&mut *ECALLS.lock().unwrap_or_else(|err| err.into_inner()))
+}
+
+/// Stand-in for the `ecall` instruction. It records the arguments of each environment call and
+/// implements the `sbi_send_ipi` function. IPIs sent to the current HART pend its software interrupt.
+pub fn ecall(eid: usize, fid: usize, arg0: usize, arg1: usize) -> isize {
+    ECALLS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .push((eid, fid, arg0, arg1));
+    if (eid, fid) != (EID_IPI, FID_SEND_IPI) {
+        return ERR_NOT_SUPPORTED;
+    }
+    let (hart_mask, hart_mask_base) = (arg0, arg1);
+    if hart_mask_base >= N_HARTS {
+        return ERR_INVALID_PARAM;
+    }
+    let current = hart_id();
+    if current >= hart_mask_base && (hart_mask >> (current - hart_mask_base)) & 1 == 1 {
+        riscv_slic::sim::pend();
+    }
+    0
+}
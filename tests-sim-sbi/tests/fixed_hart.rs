@@ -0,0 +1,64 @@
+use riscv_slic::sbi::{EID_IPI, FID_SEND_IPI};
+use riscv_slic::sim::{is_pending, lock_hart};
+use tests_sim_sbi::{set_hart_id, take_ecalls};
+
+/// Minimal PAC of the simulated platform.
+mod pac {
+    pub mod interrupt {
+        use riscv_slic::riscv::{result::Error, HartIdNumber};
+
+        /// HARTs of the simulated platform.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum Hart {
+            H0 = 0,
+            H1 = 1,
+        }
+
+        unsafe impl HartIdNumber for Hart {
+            const MAX_HART_ID_NUMBER: usize = 1;
+
+            fn number(self) -> usize {
+                self as usize
+            }
+
+            fn from_number(value: usize) -> riscv_slic::riscv::result::Result<Self> {
+                match value {
+                    0 => Ok(Self::H0),
+                    1 => Ok(Self::H1),
+                    _ => Err(Error::InvalidVariant(value)),
+                }
+            }
+        }
+    }
+}
+
+// generate SLIC code for this test. IPIs always target HART 1
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [Soft = 1],
+    backend = [hart_id = H1, ecall = hook(tests_sim_sbi::ecall)]
+);
+
+/// Handler for Soft task. This test does not dispatch it.
+#[riscv_slic::handler(Soft)]
+fn soft() {}
+
+#[test]
+fn pend_sends_ipi_to_given_hart() {
+    let _hart = lock_hart();
+    riscv_slic::disable();
+    take_ecalls();
+
+    // HART 0 sends the IPI to HART 1, so its software interrupt is not pended
+    set_hart_id(0);
+    unsafe { slic::__riscv_slic_swi_pend() };
+    assert_eq!(take_ecalls(), [(EID_IPI, FID_SEND_IPI, 1, 1)]);
+    assert!(!is_pending());
+
+    set_hart_id(1);
+    unsafe { slic::__riscv_slic_swi_pend() };
+    assert_eq!(take_ecalls(), [(EID_IPI, FID_SEND_IPI, 1, 1)]);
+    assert!(is_pending());
+    unsafe { slic::__riscv_slic_swi_unpend() };
+    assert!(!is_pending());
+}
@@ -0,0 +1,98 @@
+use riscv_slic::sbi::{send_ipi, EID_IPI, FID_SEND_IPI};
+use riscv_slic::sim::{is_pending, lock_hart};
+use std::sync::{Mutex, MutexGuard};
+use tests_sim_sbi::{set_hart_id, take_ecalls, ERR_INVALID_PARAM};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test. IPIs target the current HART through the SBI stand-in
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftHigh = 2],
+    backend = [
+        hart_id = current(tests_sim_sbi::hart_id),
+        ecall = hook(tests_sim_sbi::ecall),
+    ]
+);
+use slic::{Priority, SoftwareInterrupt};
+
+/// Log of events recorded by the software interrupt handlers.
+static LOG: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+fn take_log() -> Vec<&'static str> {
+    core::mem::take(&mut *LOG.lock().unwrap())
+}
+
+/// Handler for SoftLow task (low priority). It pends SoftHigh, which sends another IPI.
+#[riscv_slic::handler(SoftLow)]
+fn soft_low() {
+    LOG.lock().unwrap().push("SoftLow");
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+}
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high() {
+    LOG.lock().unwrap().push("SoftHigh");
+}
+
+/// Takes the simulated hart, moves the tests to HART 0, and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    set_hart_id(0);
+    unsafe { riscv_slic::set_threshold(Priority::P0) };
+    take_ecalls();
+    take_log();
+    hart
+}
+
+#[test]
+fn send_ipi_arguments() {
+    let _hart = setup();
+
+    assert_eq!(unsafe { send_ipi(0) }, Ok(()));
+    assert_eq!(unsafe { send_ipi(2) }, Ok(()));
+    assert_eq!(unsafe { send_ipi(3) }, Err(ERR_INVALID_PARAM));
+    assert_eq!(
+        take_ecalls(),
+        [
+            (0x735049, 0, 1, 0),
+            (0x735049, 0, 1, 2),
+            (0x735049, 0, 1, 3)
+        ]
+    );
+    assert_eq!((EID_IPI, FID_SEND_IPI), (0x735049, 0));
+    // the IPI to HART 0 pended the software interrupt of the current HART
+    assert!(is_pending());
+}
+
+#[test]
+fn pend_sends_ipis_to_current_hart() {
+    let _hart = setup();
+    set_hart_id(2);
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    assert_eq!(take_log(), ["SoftLow", "SoftHigh"]);
+    assert_eq!(
+        take_ecalls(),
+        [(EID_IPI, FID_SEND_IPI, 1, 2), (EID_IPI, FID_SEND_IPI, 1, 2)]
+    );
+    // the SLIC handler cleared the IPI before dispatching
+    assert!(!is_pending());
+}
+
+#[test]
+fn unpend_clears_ipi() {
+    let _hart = setup();
+    set_hart_id(1);
+
+    unsafe { slic::__riscv_slic_swi_pend() };
+    assert_eq!(take_ecalls(), [(EID_IPI, FID_SEND_IPI, 1, 1)]);
+    assert!(is_pending());
+    unsafe { slic::__riscv_slic_swi_unpend() };
+    assert!(!is_pending());
+    assert!(take_log().is_empty());
+}
@@ -0,0 +1,20 @@
+//! Helpers for testing the SLIC on the host using the simulation backend.
+//!
+//! Run the tests with `cargo test --package tests-sim --target <host target>`.
+
+use std::sync::Mutex;
+
+/// Log of events recorded by the software interrupt handlers under test.
+static LOG: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// Appends an event to the log.
+pub fn log(event: &'static str) {
+    LOG.lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .push(event);
+}
+
+/// Returns all the events recorded so far and clears the log.
+pub fn take_log() -> Vec<&'static str> {
+    core::mem::take(&mut *LOG.lock().unwrap_or_else(|err| err.into_inner()))
+}
@@ -0,0 +1,82 @@
+use riscv_slic::sim::lock_hart;
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [Doorbell = 1, Packet = 2],
+    counting = [Packet],
+    out_of_range = ignore
+);
+use slic::{Priority, SoftwareInterrupt};
+
+/// Handler for Doorbell task. Repeated pends are coalesced.
+#[riscv_slic::handler(Doorbell)]
+fn doorbell() {
+    log("Doorbell");
+}
+
+/// Handler for Packet task. It runs once per pend request.
+#[riscv_slic::handler(Packet)]
+fn packet() {
+    log("Packet");
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn counting_interrupts_are_not_coalesced() {
+    let _hart = setup();
+
+    riscv_slic::run(Priority::P2, || {
+        for _ in 0..3 {
+            riscv_slic::pend(SoftwareInterrupt::Packet);
+            riscv_slic::pend(SoftwareInterrupt::Doorbell);
+        }
+    });
+    assert_eq!(take_log(), ["Packet", "Packet", "Packet", "Doorbell"]);
+}
+
+#[test]
+fn unpend_discards_all_requests() {
+    let _hart = setup();
+
+    riscv_slic::run(Priority::P2, || {
+        riscv_slic::pend(SoftwareInterrupt::Packet);
+        riscv_slic::pend(SoftwareInterrupt::Packet);
+        assert!(riscv_slic::take_pending(SoftwareInterrupt::Packet));
+    });
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn static_priorities() {
+    let _hart = setup();
+
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::Doorbell), 1);
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::Packet), 2);
+}
+
+#[test]
+fn out_of_range_requests_are_ignored() {
+    let _hart = setup();
+
+    assert_eq!(unsafe { slic::__riscv_slic_pend(2) }, Ok(()));
+    assert_eq!(unsafe { slic::__riscv_slic_is_pending(2) }, Ok(false));
+    assert_eq!(unsafe { slic::__riscv_slic_get_priority(2) }, Ok(0));
+    assert!(take_log().is_empty());
+}
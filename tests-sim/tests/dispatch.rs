@@ -0,0 +1,404 @@
+use riscv_slic::sim::{self, lock_hart};
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow, SoftMedium, SoftHigh],
+    priorities = 3
+);
+use slic::{Priority, SoftwareInterrupt};
+
+/// Handler for SoftHigh task (high priority).
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high() {
+    log("start SoftHigh");
+    assert!(riscv_slic::is_running(SoftwareInterrupt::SoftHigh));
+    log("stop SoftHigh");
+}
+
+/// Handler for SoftMedium task (medium priority). This task pends both SoftLow and SoftHigh.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium() {
+    log("start SoftMedium");
+    assert!(riscv_slic::is_running(SoftwareInterrupt::SoftMedium));
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+    // SoftMedium was preempted by SoftHigh, but it is still running
+    assert!(riscv_slic::is_running(SoftwareInterrupt::SoftMedium));
+    log("stop SoftMedium");
+}
+
+/// Handler for SoftLow task (low priority).
+#[riscv_slic::handler(SoftLow)]
+fn soft_low() {
+    log("start SoftLow");
+    log("stop SoftLow");
+}
+
+/// Takes the simulated hart and configures the SLIC as in the CLINT example.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::set_priority(SoftwareInterrupt::SoftLow, Priority::P1);
+        riscv_slic::set_priority(SoftwareInterrupt::SoftMedium, Priority::P2);
+        riscv_slic::set_priority(SoftwareInterrupt::SoftHigh, Priority::P3);
+        riscv_slic::enable_interrupt(SoftwareInterrupt::SoftLow);
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn pend_runs_handler() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    assert_eq!(take_log(), ["start SoftLow", "stop SoftLow"]);
+    assert!(!sim::is_pending());
+}
+
+#[test]
+fn higher_priority_preempts() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+    assert_eq!(
+        take_log(),
+        [
+            "start SoftMedium",
+            "start SoftHigh",
+            "stop SoftHigh",
+            "stop SoftMedium",
+            "start SoftLow",
+            "stop SoftLow",
+        ]
+    );
+}
+
+#[test]
+fn disabled_slic_defers_interrupts() {
+    let _hart = setup();
+
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+    assert!(take_log().is_empty());
+
+    unsafe { riscv_slic::enable() };
+    assert_eq!(
+        take_log(),
+        [
+            "start SoftHigh",
+            "stop SoftHigh",
+            "start SoftLow",
+            "stop SoftLow"
+        ]
+    );
+}
+
+#[test]
+fn zero_priority_is_ignored() {
+    let _hart = setup();
+    unsafe {
+        riscv_slic::set_priority(SoftwareInterrupt::SoftLow, Priority::P0);
+        riscv_slic::enable();
+    }
+
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn set_priority_requeues_pending() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::run(Priority::P2, || {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        log("raise SoftLow");
+        unsafe { riscv_slic::set_priority(SoftwareInterrupt::SoftLow, Priority::P3) };
+        log("cancel SoftMedium");
+        unsafe { riscv_slic::set_priority(SoftwareInterrupt::SoftMedium, Priority::P0) };
+    });
+    assert_eq!(
+        take_log(),
+        [
+            "raise SoftLow",
+            "start SoftLow",
+            "stop SoftLow",
+            "cancel SoftMedium",
+        ]
+    );
+}
+
+#[test]
+fn unpend_cancels_pending() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::run(Priority::P3, || {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+        assert!(riscv_slic::take_pending(SoftwareInterrupt::SoftHigh));
+        assert!(!riscv_slic::take_pending(SoftwareInterrupt::SoftHigh));
+        assert!(!riscv_slic::take_pending(SoftwareInterrupt::SoftMedium));
+    });
+    assert_eq!(take_log(), ["start SoftLow", "stop SoftLow"]);
+
+    riscv_slic::run(Priority::P3, || {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        riscv_slic::unpend(SoftwareInterrupt::SoftLow);
+    });
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn query_status() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftLow), 1);
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftHigh), 3);
+    riscv_slic::run(Priority::P3, || {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        assert!(riscv_slic::is_pending(SoftwareInterrupt::SoftLow));
+        assert!(!riscv_slic::is_pending(SoftwareInterrupt::SoftMedium));
+        assert!(!riscv_slic::is_running(SoftwareInterrupt::SoftLow));
+    });
+    assert!(!riscv_slic::is_pending(SoftwareInterrupt::SoftLow));
+
+    // handlers are no longer running once they are done
+    riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+    assert!(!riscv_slic::is_running(SoftwareInterrupt::SoftMedium));
+    assert!(!riscv_slic::is_running(SoftwareInterrupt::SoftHigh));
+    take_log();
+}
+
+#[test]
+fn disabled_interrupts_are_latched() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::disable_interrupt(SoftwareInterrupt::SoftLow);
+    assert!(!riscv_slic::is_interrupt_enabled(
+        SoftwareInterrupt::SoftLow
+    ));
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    assert!(riscv_slic::is_pending(SoftwareInterrupt::SoftLow));
+    assert!(take_log().is_empty());
+
+    unsafe { riscv_slic::enable_interrupt(SoftwareInterrupt::SoftLow) };
+    assert!(riscv_slic::is_interrupt_enabled(SoftwareInterrupt::SoftLow));
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftLow), 1);
+    assert_eq!(take_log(), ["start SoftLow", "stop SoftLow"]);
+}
+
+#[test]
+fn run_masks_lower_priorities() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    riscv_slic::run(Priority::P2, || {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+        log("run");
+    });
+    log("done");
+    assert_eq!(
+        take_log(),
+        [
+            "start SoftHigh",
+            "stop SoftHigh",
+            "run",
+            "start SoftLow",
+            "stop SoftLow",
+            "done",
+        ]
+    );
+    assert_eq!(riscv_slic::get_threshold(), 0);
+}
+
+#[test]
+fn lock_defers_until_released() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    let mut counter = 0;
+    let res = unsafe {
+        riscv_slic::lock(&mut counter, Priority::P3, |counter| {
+            riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+            log("lock");
+            *counter += 1;
+            *counter
+        })
+    };
+    assert_eq!(res, 1);
+    assert_eq!(take_log(), ["lock", "start SoftHigh", "stop SoftHigh"]);
+}
+
+/// Pends SoftLow with the threshold raised, returning early if `early` is `true`.
+fn pend_with_guard(early: bool) -> Result<(), ()> {
+    let _guard = riscv_slic::raise(Priority::P2);
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    if early {
+        return Err(());
+    }
+    log("late return");
+    Ok(())
+}
+
+#[test]
+fn guard_restores_threshold() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    assert_eq!(pend_with_guard(true), Err(()));
+    assert_eq!(take_log(), ["start SoftLow", "stop SoftLow"]);
+    assert_eq!(pend_with_guard(false), Ok(()));
+    assert_eq!(take_log(), ["late return", "start SoftLow", "stop SoftLow"]);
+    assert_eq!(riscv_slic::get_threshold(), 0);
+}
+
+#[test]
+fn nested_guards() {
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    let outer = riscv_slic::raise(Priority::P2);
+    assert!(outer.is_raised());
+    {
+        // lower priorities do not modify the threshold
+        let inner = riscv_slic::raise(Priority::P1);
+        assert!(!inner.is_raised());
+        assert_eq!(riscv_slic::get_threshold(), 2);
+        let inner = riscv_slic::raise(Priority::P3);
+        assert!(inner.is_raised());
+        assert_eq!(riscv_slic::get_threshold(), 3);
+    }
+    assert_eq!(riscv_slic::get_threshold(), 2);
+    drop(outer);
+    assert_eq!(riscv_slic::get_threshold(), 0);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "threshold guards must be dropped in LIFO order")]
+fn guards_dropped_out_of_order() {
+    let _hart = setup();
+
+    let outer = riscv_slic::raise(Priority::P1);
+    let _inner = riscv_slic::raise(Priority::P2);
+    drop(outer);
+}
+
+#[test]
+fn priority_levels() {
+    use riscv_slic::PriorityNumber;
+
+    assert_eq!(Priority::MAX_PRIORITY_NUMBER, 3);
+    assert_eq!(Priority::from_number(2), Ok(Priority::P2));
+    assert_eq!(Priority::from_number(4), Err(4));
+    assert!(Priority::P1 < Priority::P3);
+}
+
+/// Interrupt source that does not belong to the SLIC of this test.
+#[derive(Clone, Copy)]
+struct Foreign;
+
+unsafe impl riscv_slic::InterruptNumber for Foreign {
+    const MAX_INTERRUPT_NUMBER: u16 = 7;
+
+    fn number(self) -> u16 {
+        7
+    }
+
+    fn from_number(value: u16) -> Result<Self, u16> {
+        match value {
+            7 => Ok(Foreign),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Priority level that is not valid for the SLIC of this test.
+#[derive(Clone, Copy)]
+struct Unknown;
+
+unsafe impl riscv_slic::PriorityNumber for Unknown {
+    const MAX_PRIORITY_NUMBER: u8 = 4;
+
+    fn number(self) -> u8 {
+        4
+    }
+
+    fn from_number(value: u8) -> Result<Self, u8> {
+        match value {
+            4 => Ok(Unknown),
+            _ => Err(value),
+        }
+    }
+}
+
+#[test]
+fn try_variants_report_misuse() {
+    use riscv_slic::Error;
+
+    let _hart = setup();
+    unsafe { riscv_slic::enable() };
+
+    assert_eq!(
+        riscv_slic::try_pend(Foreign),
+        Err(Error::InvalidInterrupt(7))
+    );
+    assert_eq!(
+        unsafe { riscv_slic::try_set_priority(Foreign, Priority::P1) },
+        Err(Error::InvalidInterrupt(7))
+    );
+    assert_eq!(
+        unsafe { riscv_slic::try_set_priority(SoftwareInterrupt::SoftLow, Unknown) },
+        Err(Error::PriorityOutOfRange)
+    );
+    assert_eq!(
+        unsafe { riscv_slic::try_set_threshold(Unknown) },
+        Err(Error::PriorityOutOfRange)
+    );
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftLow), 1);
+    assert_eq!(riscv_slic::get_threshold(), 0);
+    assert!(take_log().is_empty());
+
+    // raw entry points return an error by default
+    assert_eq!(
+        unsafe { slic::__riscv_slic_pend(3) },
+        Err(Error::InvalidInterrupt(3))
+    );
+    assert_eq!(
+        unsafe { slic::__riscv_slic_unpend(u16::MAX) },
+        Err(Error::InvalidInterrupt(u16::MAX))
+    );
+
+    assert_eq!(riscv_slic::try_pend(SoftwareInterrupt::SoftLow), Ok(()));
+    assert_eq!(take_log(), ["start SoftLow", "stop SoftLow"]);
+    assert_eq!(
+        unsafe { riscv_slic::try_set_threshold(Priority::P3) },
+        Ok(())
+    );
+    assert_eq!(riscv_slic::get_threshold(), 3);
+}
+
+#[test]
+#[should_panic(expected = "priority ceiling out of range")]
+fn mutex_ceiling_above_max_priority() {
+    static DATA: riscv_slic::PriorityMutex<u8, 4> = riscv_slic::PriorityMutex::new(0);
+
+    let _hart = setup();
+    DATA.lock(|data| *data += 1);
+}
@@ -0,0 +1,140 @@
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::{Poll, Waker};
+use riscv_slic::executor::{Executor, TaskFuture};
+use riscv_slic::sim::lock_hart;
+use std::sync::{Mutex, MutexGuard};
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(pac = pac, swi = [LowExecutor = 1, HighExecutor = 2]);
+use slic::{Priority, SoftwareInterrupt};
+
+static LOW: Executor<SoftwareInterrupt, 2> = Executor::new(SoftwareInterrupt::LowExecutor);
+static HIGH: Executor<SoftwareInterrupt, 1> = Executor::new(SoftwareInterrupt::HighExecutor);
+
+#[riscv_slic::handler(LowExecutor)]
+fn low_executor() {
+    unsafe { LOW.poll() };
+}
+
+#[riscv_slic::handler(HighExecutor)]
+fn high_executor() {
+    unsafe { HIGH.poll() };
+}
+
+/// Minimal signal for synchronizing tasks with the test.
+struct Signal(Mutex<(bool, Option<Waker>)>);
+
+impl Signal {
+    const fn new() -> Self {
+        Self(Mutex::new((false, None)))
+    }
+
+    fn set(&self) {
+        let waker = {
+            let mut state = self.0.lock().unwrap();
+            state.0 = true;
+            state.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    async fn wait(&self) {
+        poll_fn(|cx| {
+            let mut state = self.0.lock().unwrap();
+            if core::mem::take(&mut state.0) {
+                Poll::Ready(())
+            } else {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// Leaks a future to get a task future.
+fn task(future: impl Future<Output = ()> + Send + 'static) -> TaskFuture {
+    Pin::static_mut(Box::leak(Box::new(future)))
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn wake_polls_task() {
+    static SIGNAL: Signal = Signal::new();
+    let _hart = setup();
+
+    LOW.spawn(task(async {
+        log("start");
+        SIGNAL.wait().await;
+        log("done");
+    }))
+    .unwrap();
+    assert_eq!(take_log(), ["start"]);
+    assert!(!riscv_slic::is_pending(SoftwareInterrupt::LowExecutor));
+
+    SIGNAL.set();
+    assert_eq!(take_log(), ["done"]);
+}
+
+#[test]
+fn high_priority_executor_preempts() {
+    static LOW_SIGNAL: Signal = Signal::new();
+    static HIGH_SIGNAL: Signal = Signal::new();
+    let _hart = setup();
+
+    HIGH.spawn(task(async {
+        HIGH_SIGNAL.wait().await;
+        log("high");
+    }))
+    .unwrap();
+    LOW.spawn(task(async {
+        LOW_SIGNAL.wait().await;
+        log("low start");
+        // waking the high priority task preempts the low priority task
+        HIGH_SIGNAL.set();
+        log("low done");
+    }))
+    .unwrap();
+    assert!(take_log().is_empty());
+
+    LOW_SIGNAL.set();
+    assert_eq!(take_log(), ["low start", "high", "low done"]);
+}
+
+#[test]
+fn spawn_fails_when_full() {
+    static SIGNAL: Signal = Signal::new();
+    let _hart = setup();
+
+    // tasks are not polled until the SLIC is enabled
+    riscv_slic::disable();
+    HIGH.spawn(task(async { SIGNAL.wait().await })).unwrap();
+    assert_eq!(
+        HIGH.spawn(task(async {})),
+        Err(riscv_slic::Error::ExecutorFull)
+    );
+    unsafe { riscv_slic::enable() };
+
+    // finished tasks release their slot
+    SIGNAL.set();
+    HIGH.spawn(task(async { log("spawned") })).unwrap();
+    assert_eq!(take_log(), ["spawned"]);
+}
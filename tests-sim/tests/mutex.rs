@@ -0,0 +1,71 @@
+use riscv_slic::{sim::lock_hart, PriorityMutex};
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(pac = pac, swi = [Producer = 1, Urgent = 3]);
+use slic::{Priority, SoftwareInterrupt};
+
+/// Buffer shared between thread mode and the Producer task.
+static BUFFER: PriorityMutex<Vec<u8>, 1> = PriorityMutex::new(Vec::new());
+
+/// Handler for Producer task. It shares BUFFER with thread mode.
+#[riscv_slic::handler(Producer)]
+fn producer() {
+    BUFFER.lock(|buffer| buffer.push(1));
+    log("Producer");
+}
+
+/// Handler for Urgent task. Its priority is above the ceiling of BUFFER.
+#[riscv_slic::handler(Urgent)]
+fn urgent() {
+    log("Urgent");
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    BUFFER.lock(|buffer| buffer.clear());
+    hart
+}
+
+#[test]
+fn lock_defers_tasks_up_to_ceiling() {
+    let _hart = setup();
+
+    BUFFER.lock(|buffer| {
+        riscv_slic::pend(SoftwareInterrupt::Producer);
+        riscv_slic::pend(SoftwareInterrupt::Urgent);
+        // Urgent preempts the lock, but Producer does not
+        assert_eq!(take_log(), ["Urgent"]);
+        assert!(buffer.is_empty());
+    });
+    assert_eq!(take_log(), ["Producer"]);
+    assert_eq!(BUFFER.lock(|buffer| buffer.clone()), [1]);
+    assert_eq!(riscv_slic::get_threshold(), 0);
+}
+
+#[test]
+#[should_panic(expected = "mutex already locked")]
+fn nested_lock_panics() {
+    static FLAG: PriorityMutex<bool, 1> = PriorityMutex::new(false);
+
+    let _hart = setup();
+    FLAG.lock(|_| FLAG.lock(|flag| *flag = true));
+}
+
+#[test]
+fn owned_mutex() {
+    let mut mutex = PriorityMutex::<u32, 2>::new(1);
+    *mutex.get_mut() += 1;
+    assert_eq!(mutex.into_inner(), 2);
+}
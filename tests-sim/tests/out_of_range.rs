@@ -0,0 +1,68 @@
+use riscv_slic::{sim::lock_hart, Error};
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [Soft0 = 1, Soft1 = 2],
+    out_of_range = fault(crate::fault)
+);
+use slic::{Priority, SoftwareInterrupt};
+
+/// Fault hook for requests with out-of-range interrupt numbers.
+fn fault(err: Error) {
+    assert_eq!(err, Error::InvalidInterrupt(2));
+    log("fault");
+}
+
+#[riscv_slic::handler(Soft0)]
+fn soft0() {
+    log("Soft0");
+}
+
+#[riscv_slic::handler(Soft1)]
+fn soft1() {
+    log("Soft1");
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn fault_hook_is_called() {
+    let _hart = setup();
+
+    assert_eq!(
+        unsafe { slic::__riscv_slic_pend(2) },
+        Err(Error::InvalidInterrupt(2))
+    );
+    assert_eq!(
+        unsafe { slic::__riscv_slic_get_priority(2) },
+        Err(Error::InvalidInterrupt(2))
+    );
+    assert_eq!(take_log(), ["fault", "fault"]);
+}
+
+#[test]
+fn valid_requests_are_served() {
+    let _hart = setup();
+
+    assert_eq!(unsafe { slic::__riscv_slic_pend(1) }, Ok(()));
+    assert_eq!(take_log(), ["Soft1"]);
+    assert_eq!(unsafe { slic::__riscv_slic_get_priority(0) }, Ok(1));
+    riscv_slic::pend(SoftwareInterrupt::Soft0);
+    assert_eq!(take_log(), ["Soft0"]);
+}
@@ -0,0 +1,110 @@
+use riscv_slic::{sim::lock_hart, Error};
+use std::sync::{Mutex, MutexGuard};
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+/// Message sent to the Rx task.
+#[derive(Debug, PartialEq)]
+pub struct Packet {
+    id: u8,
+    data: Vec<u8>,
+}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [Rx: crate::Packet; 2 = 1, Ack: u8; 4 = 2, Idle = 1]
+);
+use slic::{payload, Priority, SoftwareInterrupt};
+
+/// Packets received by the Rx task.
+static RECEIVED: Mutex<Vec<Packet>> = Mutex::new(Vec::new());
+
+/// Handler for Rx task. It receives one packet per dispatch.
+#[riscv_slic::handler(Rx)]
+fn rx(packet: Packet) {
+    log("Rx");
+    riscv_slic::pend_with(payload::Ack, packet.id).unwrap();
+    RECEIVED.lock().unwrap().push(packet);
+}
+
+/// Handler for Ack task (higher priority than Rx).
+#[riscv_slic::handler(Ack)]
+fn ack(id: u8) {
+    log(["Ack 0", "Ack 1", "Ack 2"][id as usize]);
+}
+
+/// Handler for Idle task. It does not receive any payload.
+#[riscv_slic::handler(Idle)]
+fn idle() {
+    log("Idle");
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    RECEIVED.lock().unwrap().clear();
+    hart
+}
+
+fn packet(id: u8) -> Packet {
+    Packet {
+        id,
+        data: vec![id; id as usize],
+    }
+}
+
+#[test]
+fn messages_are_dispatched_in_order() {
+    let _hart = setup();
+
+    riscv_slic::run(Priority::P2, || {
+        riscv_slic::pend_with(payload::Rx, packet(0)).unwrap();
+        riscv_slic::pend(SoftwareInterrupt::Idle);
+        riscv_slic::pend_with(payload::Rx, packet(1)).unwrap();
+        assert_eq!(
+            riscv_slic::pend_with(payload::Rx, packet(2)),
+            Err(Error::QueueFull)
+        );
+    });
+    assert_eq!(take_log(), ["Rx", "Ack 0", "Rx", "Ack 1", "Idle"]);
+    assert_eq!(*RECEIVED.lock().unwrap(), [packet(0), packet(1)]);
+
+    // the queue has room again
+    riscv_slic::pend_with(payload::Rx, packet(2)).unwrap();
+    assert_eq!(take_log(), ["Rx", "Ack 2"]);
+    assert_eq!(RECEIVED.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn pend_without_message() {
+    let _hart = setup();
+
+    // the handler only runs if there are messages in the queue
+    riscv_slic::pend(SoftwareInterrupt::Rx);
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn messages_need_a_priority() {
+    let _hart = setup();
+
+    unsafe { riscv_slic::set_priority(SoftwareInterrupt::Rx, Priority::P0) };
+    assert_eq!(
+        riscv_slic::pend_with(payload::Rx, packet(0)),
+        Err(Error::ZeroPriority(0))
+    );
+    unsafe { riscv_slic::set_priority(SoftwareInterrupt::Rx, Priority::P1) };
+    // the message was not enqueued
+    riscv_slic::pend(SoftwareInterrupt::Rx);
+    assert!(take_log().is_empty());
+    assert!(RECEIVED.lock().unwrap().is_empty());
+}
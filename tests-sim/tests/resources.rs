@@ -0,0 +1,121 @@
+use riscv_slic::sim::lock_hart;
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [SoftLow = 1, SoftMedium = 2, SoftHigh = 3],
+    resources = [
+        COUNTER: u32 = 0 => [SoftLow, SoftHigh],
+        SCRATCH: [u8; 4] = [0; 4] => [SoftLow],
+        FLAG: bool = false => [SoftMedium],
+    ]
+);
+use slic::resources::{context, COUNTER, FLAG, SCRATCH};
+use slic::{Priority, SoftwareInterrupt};
+
+/// Handler for SoftHigh task (high priority). It shares COUNTER with SoftLow.
+#[riscv_slic::handler(SoftHigh)]
+fn soft_high(cx: context::SoftHigh) {
+    COUNTER::lock(&cx, |counter| *counter += 10);
+    log("SoftHigh");
+}
+
+/// Handler for SoftMedium task (medium priority). It only uses FLAG.
+#[riscv_slic::handler(SoftMedium)]
+fn soft_medium(cx: context::SoftMedium) {
+    FLAG::lock(&cx, |flag| *flag = true);
+    log("SoftMedium");
+}
+
+/// Handler for SoftLow task (low priority). It pends higher priority tasks while holding COUNTER.
+#[riscv_slic::handler(SoftLow)]
+fn soft_low(cx: context::SoftLow) {
+    COUNTER::lock(&cx, |counter| {
+        riscv_slic::pend(SoftwareInterrupt::SoftHigh);
+        riscv_slic::pend(SoftwareInterrupt::SoftMedium);
+        // SoftHigh and SoftMedium cannot preempt the critical section
+        *counter += 1;
+        log("SoftLow");
+    });
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn ceilings() {
+    assert_eq!(COUNTER::CEILING, 3);
+    assert_eq!(SCRATCH::CEILING, 1);
+}
+
+#[test]
+fn lock_defers_users_and_higher_tasks() {
+    let _hart = setup();
+
+    let thread = unsafe { context::Thread::steal() };
+    let before = COUNTER::lock(&thread, |counter| *counter);
+    riscv_slic::pend(SoftwareInterrupt::SoftLow);
+    assert_eq!(take_log(), ["SoftLow", "SoftHigh", "SoftMedium"]);
+    assert_eq!(COUNTER::lock(&thread, |counter| *counter), before + 11);
+    assert_eq!(riscv_slic::get_threshold(), 0);
+}
+
+#[test]
+fn lock_from_thread_mode() {
+    let _hart = setup();
+
+    let thread = unsafe { context::Thread::steal() };
+    SCRATCH::lock(&thread, |scratch| {
+        riscv_slic::pend(SoftwareInterrupt::SoftLow);
+        assert_eq!(riscv_slic::get_threshold(), 1);
+        assert!(take_log().is_empty());
+        scratch[0] = 1;
+    });
+    assert_eq!(take_log(), ["SoftLow", "SoftHigh", "SoftMedium"]);
+    assert_eq!(SCRATCH::lock(&thread, |scratch| scratch[0]), 1);
+}
+
+#[test]
+#[should_panic(expected = "mutex already locked")]
+fn nested_lock_panics() {
+    let _hart = setup();
+
+    let thread = unsafe { context::Thread::steal() };
+    FLAG::lock(&thread, |_| FLAG::lock(&thread, |flag| *flag = true));
+}
+
+#[test]
+fn users_cannot_exceed_ceilings() {
+    use riscv_slic::Error;
+
+    let _hart = setup();
+
+    // SoftLow uses COUNTER (ceiling 3) and SCRATCH (ceiling 1)
+    assert_eq!(
+        unsafe { riscv_slic::try_set_priority(SoftwareInterrupt::SoftLow, Priority::P2) },
+        Err(Error::PriorityAboveCeiling { ceiling: 1 })
+    );
+    unsafe { riscv_slic::set_priority(SoftwareInterrupt::SoftLow, Priority::P3) };
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftLow), 1);
+    // lowering the priority of a user keeps the ceilings valid
+    assert_eq!(
+        unsafe { riscv_slic::try_set_priority(SoftwareInterrupt::SoftMedium, Priority::P1) },
+        Ok(())
+    );
+    assert_eq!(riscv_slic::get_priority(SoftwareInterrupt::SoftMedium), 1);
+    unsafe { riscv_slic::set_priority(SoftwareInterrupt::SoftMedium, Priority::P2) };
+}
@@ -0,0 +1,53 @@
+use riscv_slic::sim::lock_hart;
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(
+    pac = pac,
+    swi = [First = 1, Second = 1, Third = 1],
+    tie_break = lowest_number_first
+);
+use slic::{Priority, SoftwareInterrupt};
+
+#[riscv_slic::handler(First)]
+fn first() {
+    log("First");
+}
+
+#[riscv_slic::handler(Second)]
+fn second() {
+    log("Second");
+}
+
+#[riscv_slic::handler(Third)]
+fn third() {
+    log("Third");
+}
+
+/// Takes the simulated hart and configures the SLIC.
+fn setup() -> MutexGuard<'static, ()> {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    hart
+}
+
+#[test]
+fn equal_priorities_are_served_by_interrupt_number() {
+    let _hart = setup();
+
+    riscv_slic::run(Priority::P1, || {
+        riscv_slic::pend(SoftwareInterrupt::Third);
+        riscv_slic::pend(SoftwareInterrupt::First);
+        riscv_slic::pend(SoftwareInterrupt::Second);
+    });
+    assert_eq!(take_log(), ["First", "Second", "Third"]);
+}
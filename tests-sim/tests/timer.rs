@@ -0,0 +1,158 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use riscv_slic::timer::{MachineTimer, TimerQueue};
+use riscv_slic::{sim::lock_hart, Error};
+use std::sync::MutexGuard;
+use tests_sim::{log, take_log};
+
+/// The simulated hart does not access any peripheral.
+mod pac {}
+
+// generate SLIC code for this test
+riscv_slic::codegen!(pac = pac, swi = [Tick = 1, Alarm = 1, Beep = 1]);
+use slic::{Priority, SoftwareInterrupt};
+
+#[riscv_slic::handler(Tick)]
+fn tick() {
+    log("Tick");
+}
+
+#[riscv_slic::handler(Alarm)]
+fn alarm() {
+    log("Alarm");
+}
+
+#[riscv_slic::handler(Beep)]
+fn beep() {
+    log("Beep");
+}
+
+/// Emulated machine timer.
+struct Mtimer {
+    mtime: AtomicU64,
+    mtimecmp: AtomicU64,
+}
+
+impl Mtimer {
+    /// Advances the timer counter and runs the timer interrupt handler if needed.
+    fn advance(
+        &'static self,
+        queue: &TimerQueue<&'static Mtimer, SoftwareInterrupt, 3>,
+        ticks: u64,
+    ) {
+        let now = self.mtime.fetch_add(ticks, Ordering::SeqCst) + ticks;
+        if now >= self.mtimecmp.load(Ordering::SeqCst) {
+            queue.on_interrupt();
+        }
+    }
+
+    fn mtimecmp(&self) -> u64 {
+        self.mtimecmp.load(Ordering::SeqCst)
+    }
+}
+
+impl MachineTimer for &'static Mtimer {
+    fn now(&self) -> u64 {
+        self.mtime.load(Ordering::SeqCst)
+    }
+
+    fn set_compare(&self, deadline: u64) {
+        self.mtimecmp.store(deadline, Ordering::SeqCst);
+    }
+}
+
+/// Takes the simulated hart and creates a new timer queue.
+fn setup() -> (
+    MutexGuard<'static, ()>,
+    &'static Mtimer,
+    TimerQueue<&'static Mtimer, SoftwareInterrupt, 3>,
+) {
+    let hart = lock_hart();
+    riscv_slic::disable();
+    unsafe {
+        riscv_slic::set_threshold(Priority::P0);
+        riscv_slic::enable();
+    }
+    take_log();
+    let mtimer = Box::leak(Box::new(Mtimer {
+        mtime: AtomicU64::new(100),
+        mtimecmp: AtomicU64::new(u64::MAX),
+    }));
+    (hart, mtimer, TimerQueue::new(mtimer))
+}
+
+#[test]
+fn pend_at_deadlines() {
+    let (_hart, mtimer, queue) = setup();
+
+    queue.pend_at(130, SoftwareInterrupt::Alarm).unwrap();
+    queue.pend_after(10, SoftwareInterrupt::Tick).unwrap();
+    queue.pend_at(110, SoftwareInterrupt::Beep).unwrap();
+    // the comparator is set to the earliest deadline
+    assert_eq!(mtimer.mtimecmp(), 110);
+
+    mtimer.advance(&queue, 5);
+    assert!(take_log().is_empty());
+    mtimer.advance(&queue, 5);
+    // entries with the same deadline are served in FIFO order
+    assert_eq!(take_log(), ["Tick", "Beep"]);
+    assert_eq!(mtimer.mtimecmp(), 130);
+    mtimer.advance(&queue, 100);
+    assert_eq!(take_log(), ["Alarm"]);
+    assert_eq!(mtimer.mtimecmp(), u64::MAX);
+}
+
+#[test]
+fn periodic_entries() {
+    let (_hart, mtimer, queue) = setup();
+
+    queue.pend_periodic(10, SoftwareInterrupt::Tick).unwrap();
+    for _ in 0..3 {
+        mtimer.advance(&queue, 10);
+        assert_eq!(take_log(), ["Tick"]);
+    }
+    assert_eq!(mtimer.mtimecmp(), 140);
+
+    assert!(queue.cancel(SoftwareInterrupt::Tick));
+    assert!(!queue.cancel(SoftwareInterrupt::Tick));
+    assert_eq!(mtimer.mtimecmp(), u64::MAX);
+    mtimer.advance(&queue, 10);
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn periodic_entries_skip_missed_periods() {
+    let (_hart, mtimer, queue) = setup();
+
+    queue.pend_periodic(10, SoftwareInterrupt::Tick).unwrap();
+    // the deadline is 110, so the periods ending at 120 and 130 are missed
+    mtimer.advance(&queue, 35);
+    assert_eq!(take_log(), ["Tick"]);
+    assert_eq!(mtimer.mtimecmp(), 140);
+    mtimer.advance(&queue, 5);
+    assert_eq!(take_log(), ["Tick"]);
+    assert_eq!(mtimer.mtimecmp(), 150);
+}
+
+#[test]
+fn queue_full() {
+    let (_hart, mtimer, queue) = setup();
+
+    for interrupt in [SoftwareInterrupt::Tick, SoftwareInterrupt::Alarm] {
+        queue.pend_after(10, interrupt).unwrap();
+    }
+    queue.pend_periodic(20, SoftwareInterrupt::Beep).unwrap();
+    assert_eq!(
+        queue.pend_after(5, SoftwareInterrupt::Tick),
+        Err(Error::QueueFull)
+    );
+
+    // expired entries release their slot, but periodic entries keep it
+    mtimer.advance(&queue, 20);
+    assert_eq!(take_log(), ["Tick", "Alarm", "Beep"]);
+    queue.pend_after(5, SoftwareInterrupt::Tick).unwrap();
+    queue.pend_after(5, SoftwareInterrupt::Alarm).unwrap();
+    assert_eq!(
+        queue.pend_after(5, SoftwareInterrupt::Beep),
+        Err(Error::QueueFull)
+    );
+}